@@ -139,5 +139,31 @@ fn edit_cargo_toml(c: &mut Criterion) {
     });
 }
 
-criterion_group!(benches, load_cargo_toml, edit_cargo_toml);
+// Count the words of a 10,000 lines document
+fn word_count(c: &mut Criterion) {
+    let lines = (0..10_000)
+        .map(|i| Line {
+            text: format!("line {} with some words in it\n", i),
+            cursor: vec![],
+            styles: vec![],
+            line_num: Some(i + 1),
+        })
+        .collect();
+    let mut linecache = LineCache::default();
+    linecache.update(Update {
+        rev: None,
+        operations: vec![Operation {
+            operation_type: Insert,
+            nb_lines: 10_000,
+            line_num: None,
+            lines,
+        }],
+        pristine: true,
+        view_id: ViewId(1),
+    });
+
+    c.bench_function("word_count", move |b| b.iter(|| linecache.word_count()));
+}
+
+criterion_group!(benches, load_cargo_toml, edit_cargo_toml, word_count);
 criterion_main!(benches);
@@ -1,3 +1,5 @@
+use std::collections::BTreeSet;
+
 use crate::{Line, Operation, OperationType, Update};
 
 /// Line cache struct to work with xi update protocol.
@@ -26,6 +28,14 @@ impl LineCache {
         &self.lines
     }
 
+    /// Retrieve the line at position `doc_line` in the document, counting
+    /// the invalid lines before the cache. Return `None` if that line is
+    /// not valid in the cache.
+    pub fn get_line_absolute(&self, doc_line: u64) -> Option<&Line> {
+        let index = doc_line.checked_sub(self.invalid_before)?;
+        self.lines.get(index as usize)
+    }
+
     /// Retrieve the total height of the linecache
     pub fn height(&self) -> u64 {
         self.before() + self.lines.len() as u64 + self.after()
@@ -33,10 +43,16 @@ impl LineCache {
 
     /// Handle an xi-core update.
     pub fn update(&mut self, update: Update) {
+        self.apply_operations(update.operations);
+    }
+
+    /// Apply the operations of an update directly, without wrapping
+    /// them in an `Update`.
+    pub fn apply_operations(&mut self, operations: Vec<Operation>) {
         debug!("line cache before update: {:?}", self);
         debug!(
             "operations to be applied to the line cache: {:?}",
-            &update.operations
+            &operations
         );
 
         let mut helper = UpdateHelper {
@@ -44,12 +60,266 @@ impl LineCache {
             new_cache: LineCache::default(),
         };
 
-        helper.update(update.operations);
+        helper.update(operations);
     }
 
     pub fn is_empty(&self) -> bool {
         self.lines.is_empty()
     }
+
+    /// Return the percentage of the document's lines that are valid in
+    /// the cache, between 0 and 100. An empty document is fully valid.
+    pub fn valid_lines_percent(&self) -> f64 {
+        if self.height() == 0 {
+            return 100.0;
+        }
+        let percent = self.lines.len() as f64 / self.height() as f64 * 100.0;
+        percent.clamp(0.0, 100.0)
+    }
+
+    /// Search the valid lines for `needle`. For each line that contains
+    /// it, yield the line's position in the document (counting the
+    /// invalid lines before the cache) and the byte offsets of the
+    /// matches in the line's text.
+    pub fn search_text<'a>(
+        &'a self,
+        needle: &str,
+        case_sensitive: bool,
+    ) -> impl Iterator<Item = (u64, Vec<usize>)> + 'a {
+        let needle = if case_sensitive {
+            needle.to_owned()
+        } else {
+            needle.to_lowercase()
+        };
+        let first_line = self.invalid_before;
+        // an empty needle matches nothing
+        let searchable = !needle.is_empty();
+        self.lines
+            .iter()
+            .enumerate()
+            .filter(move |_| searchable)
+            .filter_map(move |(index, line)| {
+                let offsets: Vec<usize> = if case_sensitive {
+                    line.text
+                        .match_indices(needle.as_str())
+                        .map(|(offset, _)| offset)
+                        .collect()
+                } else {
+                    find_lowercase(&line.text, &needle)
+                };
+                if offsets.is_empty() {
+                    None
+                } else {
+                    Some((first_line + index as u64, offsets))
+                }
+            })
+    }
+
+    /// Find the word under `byte_col` in the line at position `line_idx`
+    /// in the document. A word is a run of alphanumeric characters. If
+    /// there is one, return `(line_idx, word_start_byte, word_end_byte)`.
+    /// This makes it possible to show the selection of a double-clicked
+    /// word without waiting for xi-core.
+    pub fn word_at(&self, line_idx: u64, byte_col: usize) -> Option<(u64, usize, usize)> {
+        let text = &self.get_line_absolute(line_idx)?.text;
+        if byte_col >= text.len() {
+            return None;
+        }
+
+        let mut offset = byte_col;
+        while !text.is_char_boundary(offset) {
+            offset -= 1;
+        }
+        if !text[offset..].chars().next()?.is_alphanumeric() {
+            return None;
+        }
+
+        let start = text[..offset]
+            .char_indices()
+            .rev()
+            .take_while(|(_, c)| c.is_alphanumeric())
+            .fold(offset, |_, (i, _)| i);
+        let end = text[offset..]
+            .char_indices()
+            .find(|(_, c)| !c.is_alphanumeric())
+            .map_or(text.len(), |(i, _)| offset + i);
+        Some((line_idx, start, end))
+    }
+
+    /// Return the position in the document of the valid lines that are
+    /// longer than `limit` characters.
+    pub fn column_ruler_violations(&self, limit: usize) -> impl Iterator<Item = u64> + '_ {
+        let first_line = self.invalid_before;
+        self.lines
+            .iter()
+            .enumerate()
+            .filter(move |(_, line)| line.text.chars().count() > limit)
+            .map(move |(index, _)| first_line + index as u64)
+    }
+
+    /// Count the whitespace delimited words in the valid lines.
+    pub fn word_count(&self) -> usize {
+        self.lines
+            .iter()
+            .map(|line| line.text.split_whitespace().count())
+            .sum()
+    }
+
+    /// Iterate over the paragraphs of the valid lines, ie the runs of
+    /// consecutive lines that are not blank.
+    pub fn iter_paragraphs(&self) -> impl Iterator<Item = &[Line]> {
+        self.lines
+            .split(|line| line.text.trim().is_empty())
+            .filter(|paragraph| !paragraph.is_empty())
+    }
+
+    /// Return the display columns at which indent guides should be drawn
+    /// for the valid lines, sorted and deduplicated. Tabs advance to the
+    /// next multiple of `tab_width`, and blank lines are ignored.
+    pub fn indent_guides(&self, tab_width: u8) -> Vec<u64> {
+        let tab_width = u64::from(tab_width.max(1));
+        let mut stops = BTreeSet::new();
+        for line in &self.lines {
+            if line.text.trim().is_empty() {
+                continue;
+            }
+            let mut indent = 0;
+            for c in line.text.chars() {
+                match c {
+                    ' ' => indent += 1,
+                    '\t' => indent += tab_width - indent % tab_width,
+                    _ => break,
+                }
+            }
+            stops.extend((0..indent).step_by(tab_width as usize));
+        }
+        stops.into_iter().collect()
+    }
+
+    /// Take a serializable snapshot of the cache, e.g. to persist it
+    /// across sessions.
+    pub fn snapshot(&self) -> LineCacheSnapshot {
+        LineCacheSnapshot {
+            invalid_before: self.invalid_before,
+            lines: self.lines.clone(),
+            invalid_after: self.invalid_after,
+        }
+    }
+
+    /// Rebuild a cache from a snapshot.
+    pub fn restore(snap: LineCacheSnapshot) -> LineCache {
+        LineCache {
+            invalid_before: snap.invalid_before,
+            lines: snap.lines,
+            invalid_after: snap.invalid_after,
+        }
+    }
+}
+
+/// Iterator over all the lines of a [`LineCache`], including the invalid
+/// ones. It yields the position of each line in the document, and the line
+/// if it is valid.
+pub struct LineCacheIter<'a> {
+    cache: &'a LineCache,
+    index: u64,
+}
+
+impl<'a> Iterator for LineCacheIter<'a> {
+    type Item = (u64, Option<&'a Line>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.index >= self.cache.height() {
+            return None;
+        }
+        let index = self.index;
+        self.index += 1;
+        let line = index
+            .checked_sub(self.cache.invalid_before)
+            .and_then(|i| self.cache.lines.get(i as usize));
+        Some((index, line))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = (self.cache.height() - self.index) as usize;
+        (remaining, Some(remaining))
+    }
+}
+
+impl<'a> IntoIterator for &'a LineCache {
+    type Item = (u64, Option<&'a Line>);
+    type IntoIter = LineCacheIter<'a>;
+
+    fn into_iter(self) -> LineCacheIter<'a> {
+        LineCacheIter {
+            cache: self,
+            index: 0,
+        }
+    }
+}
+
+/// Serializable state of a [`LineCache`]. See [`LineCache::snapshot`]
+/// and [`LineCache::restore`].
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct LineCacheSnapshot {
+    invalid_before: u64,
+    lines: Vec<Line>,
+    invalid_after: u64,
+}
+
+/// Soft-wrapped view of a [`LineCache`], for frontends that wrap lines
+/// themselves instead of letting xi-core do it. Each visual row is
+/// stored as `(doc_line, start_byte, end_byte)`, where `doc_line` is the
+/// position of the line in the document, counting the invalid lines
+/// before the cache.
+#[derive(Clone, Debug)]
+pub struct WrappedLineCache<'a> {
+    cache: &'a LineCache,
+    rows: Vec<(u64, usize, usize)>,
+}
+
+impl<'a> WrappedLineCache<'a> {
+    /// Split the lines of `cache` into visual rows of at most
+    /// `wrap_width` characters. A `wrap_width` of 0 disables wrapping.
+    pub fn build(cache: &'a LineCache, wrap_width: usize) -> WrappedLineCache<'a> {
+        let mut rows = Vec::with_capacity(cache.lines.len());
+        for (index, line) in cache.lines.iter().enumerate() {
+            let index = cache.invalid_before + index as u64;
+            if wrap_width == 0 || line.text.is_empty() {
+                rows.push((index, 0, line.text.len()));
+                continue;
+            }
+            let mut start = 0;
+            for (nb_chars, (offset, _)) in line.text.char_indices().enumerate() {
+                if nb_chars > 0 && nb_chars % wrap_width == 0 {
+                    rows.push((index, start, offset));
+                    start = offset;
+                }
+            }
+            rows.push((index, start, line.text.len()));
+        }
+        WrappedLineCache { cache, rows }
+    }
+
+    /// Retrieve the line a visual row belongs to, and the text of that row.
+    pub fn get_visual_line(&self, visual_row: usize) -> Option<(&'a Line, &'a str)> {
+        let (doc_line, start, end) = *self.rows.get(visual_row)?;
+        let line = self.cache.get_line_absolute(doc_line)?;
+        Some((line, &line.text[start..end]))
+    }
+
+    /// Retrieve all the visual rows.
+    pub fn rows(&self) -> &[(u64, usize, usize)] {
+        &self.rows
+    }
+
+    /// Retrieve the number of visual rows.
+    pub fn len(&self) -> usize {
+        self.rows.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rows.is_empty()
+    }
 }
 
 #[derive(Debug)]
@@ -321,12 +591,50 @@ impl<'a> UpdateHelper<'a> {
     }
 }
 
+// Find the lowercase `needle` in `text`, ignoring case. The returned
+// offsets are offsets in `text`, not in its lowercase version, which may
+// have a different length.
+fn find_lowercase(text: &str, needle: &str) -> Vec<usize> {
+    let mut lowercase = String::with_capacity(text.len());
+    // offset in `text` of the character each byte of `lowercase` comes from
+    let mut origins = Vec::with_capacity(text.len());
+    for (offset, c) in text.char_indices() {
+        for lower in c.to_lowercase() {
+            lowercase.push(lower);
+        }
+        origins.resize(lowercase.len(), offset);
+    }
+    let mut offsets: Vec<usize> = lowercase
+        .match_indices(needle)
+        .map(|(offset, _)| origins[offset])
+        .collect();
+    offsets.dedup();
+    offsets
+}
+
 fn trim_new_line(text: &mut String) {
     if let Some('\n') = text.chars().last() {
         text.pop();
     }
 }
 
+// Build a cache with the given valid lines, surrounded by `before` and
+// `after` invalid lines.
+#[cfg(test)]
+fn cache_with(before: u64, texts: &[&str], after: u64) -> LineCache {
+    LineCache {
+        invalid_before: before,
+        lines: texts
+            .iter()
+            .map(|text| Line {
+                text: text.to_string(),
+                ..Line::default()
+            })
+            .collect(),
+        invalid_after: after,
+    }
+}
+
 #[test]
 // This test simulates a simple edit operation on a LineCache.
 fn test_cache_edit() {
@@ -381,3 +689,340 @@ fn test_cache_edit() {
         .unwrap()
     );
 }
+
+#[test]
+fn test_wrapped_line_cache() {
+    let cache = LineCache {
+        invalid_before: 2,
+        lines: serde_json::from_str::<Vec<Line>>(
+            r#"
+               [
+                 {"text":"abcdefgh", "ln":3},
+                 {"text":"", "ln":4},
+                 {"text":"éàü", "ln":5}
+               ]
+            "#,
+        )
+        .unwrap(),
+        invalid_after: 1,
+    };
+
+    let wrapped = WrappedLineCache::build(&cache, 3);
+    assert_eq!(
+        wrapped.rows(),
+        &[(2, 0, 3), (2, 3, 6), (2, 6, 8), (3, 0, 0), (4, 0, 6)][..]
+    );
+    let (line, text) = wrapped.get_visual_line(1).unwrap();
+    assert_eq!(line.line_num, Some(3));
+    assert_eq!(text, "def");
+    assert_eq!(wrapped.get_visual_line(3).unwrap().1, "");
+    assert_eq!(wrapped.get_visual_line(4).unwrap().1, "éàü");
+    assert!(wrapped.get_visual_line(5).is_none());
+
+    let wrapped = WrappedLineCache::build(&cache, 2);
+    assert_eq!(wrapped.get_visual_line(5).unwrap().1, "éà");
+    assert_eq!(wrapped.get_visual_line(6).unwrap().1, "ü");
+
+    let unwrapped = WrappedLineCache::build(&cache, 0);
+    assert_eq!(unwrapped.len(), 3);
+    assert_eq!(unwrapped.get_visual_line(0).unwrap().1, "abcdefgh");
+}
+
+#[test]
+fn test_cache_snapshot() {
+    let cache = LineCache {
+        invalid_before: 3,
+        lines: serde_json::from_str::<Vec<Line>>(
+            r#"
+               [
+                 {"text":"line4", "ln":4, "cursor":[2], "styles":[0,5,1]},
+                 {"text":"line5", "ln":5}
+               ]
+            "#,
+        )
+        .unwrap(),
+        invalid_after: 10,
+    };
+
+    let json = serde_json::to_string(&cache.snapshot()).unwrap();
+    let snap: LineCacheSnapshot = serde_json::from_str(&json).unwrap();
+    let restored = LineCache::restore(snap);
+
+    assert_eq!(restored.before(), 3);
+    assert_eq!(restored.after(), 10);
+    assert_eq!(restored.lines(), cache.lines());
+    assert_eq!(restored.height(), cache.height());
+}
+
+#[test]
+fn test_search_text() {
+    let cache = LineCache {
+        invalid_before: 10,
+        lines: serde_json::from_str::<Vec<Line>>(
+            r#"
+               [
+                 {"text":"foo bar foo", "ln":11},
+                 {"text":"nothing here", "ln":12},
+                 {"text":"Foo FOO", "ln":13},
+                 {"text":"éFoo", "ln":14}
+               ]
+            "#,
+        )
+        .unwrap(),
+        invalid_after: 5,
+    };
+
+    let matches: Vec<_> = cache.search_text("foo", true).collect();
+    assert_eq!(matches, vec![(10, vec![0, 8])]);
+
+    let matches: Vec<_> = cache.search_text("Foo", false).collect();
+    assert_eq!(
+        matches,
+        vec![(10, vec![0, 8]), (12, vec![0, 4]), (13, vec![2])]
+    );
+
+    assert_eq!(cache.search_text("baz", false).count(), 0);
+    assert_eq!(cache.search_text("", false).count(), 0);
+}
+
+#[test]
+fn test_word_at() {
+    let cache = LineCache {
+        invalid_before: 2,
+        lines: serde_json::from_str::<Vec<Line>>(
+            r#"
+               [
+                 {"text":"let foo_bar = 42;", "ln":3},
+                 {"text":"héllo wörld", "ln":4}
+               ]
+            "#,
+        )
+        .unwrap(),
+        invalid_after: 5,
+    };
+
+    assert_eq!(cache.word_at(2, 0), Some((2, 0, 3)));
+    assert_eq!(cache.word_at(2, 2), Some((2, 0, 3)));
+    assert_eq!(cache.word_at(2, 5), Some((2, 4, 7)));
+    assert_eq!(cache.word_at(2, 9), Some((2, 8, 11)));
+    assert_eq!(cache.word_at(2, 15), Some((2, 14, 16)));
+    // not on a word
+    assert_eq!(cache.word_at(2, 3), None);
+    assert_eq!(cache.word_at(2, 7), None);
+    assert_eq!(cache.word_at(2, 16), None);
+    assert_eq!(cache.word_at(2, 17), None);
+    // multi-byte characters, including in the middle of one
+    assert_eq!(cache.word_at(3, 0), Some((3, 0, 6)));
+    assert_eq!(cache.word_at(3, 2), Some((3, 0, 6)));
+    assert_eq!(cache.word_at(3, 9), Some((3, 7, 13)));
+    // lines outside of the cache
+    assert_eq!(cache.word_at(1, 0), None);
+    assert_eq!(cache.word_at(4, 0), None);
+}
+
+#[test]
+fn test_column_ruler_violations() {
+    let short = "a".repeat(79);
+    let limit = "b".repeat(80);
+    let long = "c".repeat(81);
+    // 80 characters, but more than 80 bytes
+    let multibyte = "é".repeat(80);
+    let cache = cache_with(4, &[&short, &long, &limit, &multibyte, &long], 2);
+
+    let violations: Vec<u64> = cache.column_ruler_violations(80).collect();
+    assert_eq!(violations, vec![5, 8]);
+    assert_eq!(cache.column_ruler_violations(100).count(), 0);
+}
+
+#[test]
+fn test_word_count() {
+    assert_eq!(LineCache::default().word_count(), 0);
+    assert_eq!(cache_with(0, &["", "   ", "\t"], 0).word_count(), 0);
+    assert_eq!(cache_with(0, &["foo", "bar", "baz"], 0).word_count(), 3);
+    assert_eq!(
+        cache_with(0, &["  foo   bar  ", "baz\tqux", ""], 0).word_count(),
+        4
+    );
+}
+
+#[test]
+fn test_iter_paragraphs() {
+    let cache = cache_with(0, &["", "a", "b", "  ", "", "c", "\t", "d", "e", "f"], 0);
+
+    let paragraphs: Vec<Vec<&str>> = cache
+        .iter_paragraphs()
+        .map(|paragraph| paragraph.iter().map(|line| line.text.as_str()).collect())
+        .collect();
+    assert_eq!(
+        paragraphs,
+        vec![vec!["a", "b"], vec!["c"], vec!["d", "e", "f"]]
+    );
+    assert_eq!(LineCache::default().iter_paragraphs().count(), 0);
+}
+
+#[test]
+fn test_indent_guides() {
+    let cache = cache_with(
+        0,
+        &[
+            "fn main() {",
+            "    if x {",
+            "\t\ty();",
+            "  \tz();",
+            "\t      ",
+            "}",
+        ],
+        0,
+    );
+
+    assert_eq!(cache.indent_guides(4), vec![0, 4]);
+    assert_eq!(cache.indent_guides(8), vec![0, 8]);
+    assert!(LineCache::default().indent_guides(4).is_empty());
+}
+
+#[test]
+fn test_apply_operations() {
+    let mut cache = LineCache::default();
+    cache.apply_operations(vec![
+        Operation {
+            operation_type: OperationType::Insert,
+            nb_lines: 2,
+            line_num: None,
+            lines: vec![
+                Line {
+                    text: "foo".to_string(),
+                    line_num: Some(1),
+                    ..Line::default()
+                },
+                Line {
+                    text: "bar".to_string(),
+                    line_num: Some(2),
+                    ..Line::default()
+                },
+            ],
+        },
+        Operation {
+            operation_type: OperationType::Invalidate,
+            nb_lines: 3,
+            line_num: None,
+            lines: vec![],
+        },
+    ]);
+    assert_eq!(cache.before(), 0);
+    assert_eq!(cache.after(), 3);
+    let texts: Vec<&str> = cache.lines().iter().map(|l| l.text.as_str()).collect();
+    assert_eq!(texts, vec!["foo", "bar"]);
+
+    cache.apply_operations(vec![
+        Operation {
+            operation_type: OperationType::Skip,
+            nb_lines: 1,
+            line_num: None,
+            lines: vec![],
+        },
+        Operation {
+            operation_type: OperationType::Copy,
+            nb_lines: 4,
+            line_num: Some(1),
+            lines: vec![],
+        },
+    ]);
+    assert_eq!(cache.before(), 0);
+    assert_eq!(cache.after(), 3);
+    assert_eq!(cache.lines()[0].text, "bar");
+    assert_eq!(cache.lines()[0].line_num, Some(1));
+}
+
+#[test]
+fn test_into_iter() {
+    let cache = cache_with(2, &["foo", "bar"], 1);
+
+    let lines: Vec<(u64, Option<&str>)> = (&cache)
+        .into_iter()
+        .map(|(index, line)| (index, line.map(|line| line.text.as_str())))
+        .collect();
+    assert_eq!(
+        lines,
+        vec![
+            (0, None),
+            (1, None),
+            (2, Some("foo")),
+            (3, Some("bar")),
+            (4, None)
+        ]
+    );
+    assert_eq!((&LineCache::default()).into_iter().count(), 0);
+}
+
+#[test]
+fn test_many_sequential_inserts() {
+    use crate::ViewId;
+
+    let mut cache = LineCache::default();
+    for i in 0..100u64 {
+        let mut operations = Vec::new();
+        if i > 0 {
+            operations.push(Operation {
+                operation_type: OperationType::Copy,
+                nb_lines: i,
+                line_num: Some(1),
+                lines: vec![],
+            });
+        }
+        operations.push(Operation {
+            operation_type: OperationType::Insert,
+            nb_lines: 1,
+            line_num: None,
+            lines: vec![Line {
+                text: format!("line {}\n", i),
+                line_num: Some(i + 1),
+                ..Line::default()
+            }],
+        });
+        cache.update(Update {
+            rev: None,
+            operations,
+            pristine: false,
+            view_id: ViewId(1),
+        });
+    }
+
+    assert_eq!(cache.height(), 100);
+    assert_eq!(cache.before(), 0);
+    assert_eq!(cache.after(), 0);
+    for (i, line) in cache.lines().iter().enumerate() {
+        assert_eq!(line.text, format!("line {}", i));
+        assert_eq!(line.line_num, Some(i as u64 + 1));
+    }
+}
+
+#[test]
+fn test_valid_lines_percent() {
+    assert_eq!(LineCache::default().valid_lines_percent(), 100.0);
+    assert_eq!(
+        cache_with(0, &["a", "b", "c", "d"], 0).valid_lines_percent(),
+        100.0
+    );
+    assert_eq!(cache_with(1, &["a", "b"], 1).valid_lines_percent(), 50.0);
+    assert_eq!(cache_with(3, &["a"], 0).valid_lines_percent(), 25.0);
+    assert_eq!(cache_with(5, &[], 5).valid_lines_percent(), 0.0);
+}
+
+#[test]
+fn test_get_line_absolute() {
+    let cache = cache_with(2, &["foo", "bar", "baz"], 2);
+    let text_at = |doc_line| cache.get_line_absolute(doc_line).map(|l| l.text.as_str());
+
+    // invalid lines before the cache
+    assert_eq!(text_at(0), None);
+    assert_eq!(text_at(1), None);
+    // first, middle and last lines of the cache
+    assert_eq!(text_at(2), Some("foo"));
+    assert_eq!(text_at(3), Some("bar"));
+    assert_eq!(text_at(4), Some("baz"));
+    // invalid lines after the cache, and past the end of the document
+    assert_eq!(text_at(5), None);
+    assert_eq!(text_at(6), None);
+    assert_eq!(text_at(7), None);
+    assert_eq!(text_at(u64::MAX), None);
+}
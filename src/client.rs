@@ -1,7 +1,9 @@
 use crate::errors::ClientError;
-use crate::protocol;
-use crate::structs::{ModifySelection, ViewId};
+use crate::protocol::errors::RpcError;
+use crate::protocol::{self, ConnectionState};
+use crate::structs::{FindQuery, ModifySelection, ViewId};
 use futures::{future, future::Either, Future};
+use regex::Regex;
 use serde::Serialize;
 use serde_json::Value;
 use serde_json::{from_value, to_value, Map};
@@ -29,6 +31,11 @@ fn get_edit_params<T: Serialize>(
 }
 
 impl Client {
+    /// Return the state of the connection to the core.
+    pub fn state(&self) -> ConnectionState {
+        self.0.state()
+    }
+
     /// Send a notification to the core. Most (if not all) notifications
     /// supported by the core are already implemented, so this method
     /// should not be necessary in most cases.
@@ -57,6 +64,7 @@ impl Client {
             .then(|response| match response {
                 Ok(Ok(value)) => Ok(value),
                 Ok(Err(value)) => Err(ClientError::ErrorReturned(value)),
+                Err(RpcError::Disconnected) => Err(ClientError::Disconnected),
                 Err(_) => Err(ClientError::RequestFailed),
             })
     }
@@ -102,6 +110,20 @@ impl Client {
         self.edit_notify(view_id, "scroll", Some(json!([first_line, last_line])))
     }
 
+    /// Send a `"scroll"` notification followed by a `"request_lines"`
+    /// notification for the same range, so that the core sends the lines
+    /// that became visible.
+    pub fn scroll_and_request_lines(
+        &self,
+        view_id: ViewId,
+        first_line: u64,
+        last_line: u64,
+    ) -> impl Future<Item = (), Error = ClientError> {
+        let client = self.clone();
+        self.scroll(view_id, first_line, last_line)
+            .and_then(move |_| client.request_lines(view_id, first_line, last_line))
+    }
+
     pub fn goto_line(
         &self,
         view_id: ViewId,
@@ -134,6 +156,10 @@ impl Client {
         self.edit_notify(view_id, "redo", None as Option<Value>)
     }
 
+    /// Send a `"find"` notification. The returned future fails with
+    /// `ClientError::InvalidInput` without sending anything if
+    /// `search_term` is empty, or if `regex` is set and `search_term` is
+    /// not a valid regular expression.
     pub fn find(
         &self,
         view_id: ViewId,
@@ -142,7 +168,17 @@ impl Client {
         regex: bool,
         whole_words: bool,
     ) -> impl Future<Item = (), Error = ClientError> {
-        self.edit_notify(
+        if search_term.is_empty() {
+            let err = ClientError::InvalidInput("find query cannot be empty".into());
+            return Either::B(future::err(err));
+        }
+        if regex {
+            if let Err(e) = Regex::new(search_term) {
+                let err = ClientError::InvalidInput(format!("invalid find regex: {}", e));
+                return Either::B(future::err(err));
+            }
+        }
+        Either::A(self.edit_notify(
             view_id,
             "find",
             Some(json!({
@@ -150,7 +186,17 @@ impl Client {
                 "case_sensitive": case_sensitive,
                 "regex": regex,
                 "whole_words": whole_words})),
-        )
+        ))
+    }
+
+    /// Send a `"multi_find"` notification, to search for several
+    /// queries at once.
+    pub fn find_multi(
+        &self,
+        view_id: ViewId,
+        queries: Vec<FindQuery>,
+    ) -> impl Future<Item = (), Error = ClientError> {
+        self.edit_notify(view_id, "multi_find", Some(json!({ "queries": queries })))
     }
 
     fn find_other(
@@ -392,11 +438,28 @@ impl Client {
         self.edit_notify(view_id, "insert_tab", None as Option<Value>)
     }
 
+    /// Same as [`Client::debug_rewrap`].
     pub fn f1(&self, view_id: ViewId) -> impl Future<Item = (), Error = ClientError> {
-        self.edit_notify(view_id, "debug_rewrap", None as Option<Value>)
+        self.debug_rewrap(view_id)
     }
 
+    /// Same as [`Client::debug_test_fg_spans`].
     pub fn f2(&self, view_id: ViewId) -> impl Future<Item = (), Error = ClientError> {
+        self.debug_test_fg_spans(view_id)
+    }
+
+    /// Ask the core to rewrap the view. Only useful to debug the core's
+    /// text layout.
+    pub fn debug_rewrap(&self, view_id: ViewId) -> impl Future<Item = (), Error = ClientError> {
+        self.edit_notify(view_id, "debug_rewrap", None as Option<Value>)
+    }
+
+    /// Ask the core to add test foreground spans to the view. Only useful
+    /// to debug the core's styling.
+    pub fn debug_test_fg_spans(
+        &self,
+        view_id: ViewId,
+    ) -> impl Future<Item = (), Error = ClientError> {
         self.edit_notify(view_id, "debug_test_fg_spans", None as Option<Value>)
     }
 
@@ -756,3 +819,37 @@ impl Client {
 
     // TODO: requests for plugin_rpc
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::client::InnerClient;
+
+    #[test]
+    fn find_rejects_invalid_queries() {
+        let (_inner, client) = InnerClient::new();
+        let client = Client(client);
+
+        match client.find(ViewId(1), "", false, false, false).wait() {
+            Err(ClientError::InvalidInput(_)) => {}
+            other => panic!("expected an invalid input error, got {:?}", other),
+        }
+        match client.find(ViewId(1), "foo(", false, true, false).wait() {
+            Err(ClientError::InvalidInput(_)) => {}
+            other => panic!("expected an invalid input error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn request_fails_once_disconnected() {
+        let (inner, client) = InnerClient::new();
+        let client = Client(client);
+        drop(inner);
+
+        assert_eq!(client.state(), ConnectionState::Closed);
+        match client.request("new_view", json!({})).wait() {
+            Err(ClientError::Disconnected) => {}
+            other => panic!("expected a disconnected error, got {:?}", other),
+        }
+    }
+}
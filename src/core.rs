@@ -1,9 +1,10 @@
 use crate::client::Client;
 use crate::frontend::{Frontend, FrontendBuilder};
-use crate::protocol::Endpoint;
+use crate::protocol::{Codec, Endpoint, MessageCodec};
 use crate::ClientError;
 use bytes::BytesMut;
 use futures::{Future, Poll, Stream};
+use std::ffi::OsStr;
 use std::io::{self, Read, Write};
 use std::process::Command;
 use std::process::Stdio;
@@ -55,7 +56,10 @@ impl AsyncWrite for Core {
 /// [`tokio::spawn`](https://docs.rs/tokio/0.1.21/tokio/executor/fn.spawn.html)
 /// so it will panic if the default executor is not set or if spawning
 /// onto the default executor returns an error.
-pub fn spawn<B, F>(executable: &str, builder: B) -> Result<(Client, CoreStderr), ClientError>
+pub fn spawn<B, F>(
+    executable: impl AsRef<OsStr>,
+    builder: B,
+) -> Result<(Client, CoreStderr), ClientError>
 where
     F: Frontend + 'static + Send,
     B: FrontendBuilder<Frontend = F> + 'static,
@@ -65,12 +69,31 @@ where
 
 /// Same as [`spawn`] but accepts an arbitrary [`std::process::Command`].
 pub fn spawn_command<B, F>(
+    command: Command,
+    builder: B,
+) -> Result<(Client, CoreStderr), ClientError>
+where
+    F: Frontend + 'static + Send,
+    B: FrontendBuilder<Frontend = F> + 'static,
+{
+    spawn_command_with_codec(command, builder, Codec)
+}
+
+/// Same as [`spawn_command`] but accepts the codec used to frame the
+/// messages exchanged with Xi core. For instance, [`StrictCodec`]
+/// can be used to make the endpoint fail on malformed messages
+/// instead of silently discarding them.
+///
+/// [`StrictCodec`]: struct.StrictCodec.html
+pub fn spawn_command_with_codec<B, F, C>(
     mut command: Command,
     builder: B,
+    codec: C,
 ) -> Result<(Client, CoreStderr), ClientError>
 where
     F: Frontend + 'static + Send,
     B: FrontendBuilder<Frontend = F> + 'static,
+    C: MessageCodec + 'static + Send,
 {
     info!("starting xi-core");
     let mut xi_core = command
@@ -89,7 +112,7 @@ where
         stdin,
     };
 
-    let (endpoint, client) = Endpoint::new(core, builder);
+    let (endpoint, client) = Endpoint::new(core, builder, codec);
 
     info!("spawning the Xi-RPC endpoint");
     // XXX: THIS PANICS IF THE DEFAULT EXECUTOR IS NOT SET
@@ -126,6 +149,26 @@ impl CoreStderr {
     fn new(stderr: ChildStderr) -> Self {
         CoreStderr(FramedRead::new(stderr, LineCodec {}))
     }
+
+    /// Spawn a task that logs each line from Xi core's stderr with
+    /// `debug!`, until the stream closes. Xi core blocks when its stderr
+    /// pipe is full, so the stream must be consumed one way or another.
+    ///
+    /// # Panics
+    ///
+    /// This function calls
+    /// [`tokio::spawn`](https://docs.rs/tokio/0.1.21/tokio/executor/fn.spawn.html)
+    /// so it will panic if the default executor is not set or if spawning
+    /// onto the default executor returns an error.
+    pub fn spawn_logger(self) {
+        tokio::spawn(
+            self.for_each(|line| {
+                debug!("xi-core stderr: {}", line);
+                Ok(())
+            })
+            .map_err(|e| error!("failed to read xi-core stderr: {:?}", e)),
+        );
+    }
 }
 
 impl Stream for CoreStderr {
@@ -0,0 +1,84 @@
+use std::time::{Duration, Instant};
+
+/// Keeps track of the visibility of a blinking cursor.
+#[derive(Clone, Debug)]
+pub struct CursorBlinker {
+    interval: Duration,
+    visible: bool,
+    last_toggle: Instant,
+}
+
+impl CursorBlinker {
+    /// Create a visible cursor that toggles its visibility every `interval`.
+    pub fn new(interval: Duration) -> CursorBlinker {
+        CursorBlinker {
+            interval,
+            visible: true,
+            last_toggle: Instant::now(),
+        }
+    }
+
+    /// Toggle the cursor visibility if at least one interval has elapsed
+    /// since the last toggle. Return `true` if the visibility changed,
+    /// in which case the cursor needs to be redrawn.
+    pub fn tick(&mut self, now: Instant) -> bool {
+        if now < self.last_toggle || now - self.last_toggle < self.interval {
+            return false;
+        }
+        self.visible = !self.visible;
+        self.last_toggle = now;
+        true
+    }
+
+    /// Whether the cursor should currently be drawn.
+    pub fn is_visible(&self) -> bool {
+        self.visible
+    }
+
+    /// Make the cursor visible and restart the blinking period. This
+    /// should be called when the user interacts with the editor, e.g.
+    /// on keypress, so that the cursor does not disappear while typing.
+    pub fn reset(&mut self) {
+        self.visible = true;
+        self.last_toggle = Instant::now();
+    }
+}
+
+#[test]
+fn blink() {
+    let interval = Duration::from_millis(500);
+    let mut blinker = CursorBlinker::new(interval);
+    let start = blinker.last_toggle;
+    assert!(blinker.is_visible());
+
+    assert!(!blinker.tick(start + Duration::from_millis(499)));
+    assert!(blinker.is_visible());
+
+    assert!(blinker.tick(start + interval));
+    assert!(!blinker.is_visible());
+
+    assert!(!blinker.tick(start + Duration::from_millis(700)));
+    assert!(!blinker.is_visible());
+
+    assert!(blinker.tick(start + interval * 2));
+    assert!(blinker.is_visible());
+}
+
+#[test]
+fn reset() {
+    let interval = Duration::from_millis(500);
+    let mut blinker = CursorBlinker::new(interval);
+    let start = blinker.last_toggle;
+    assert!(blinker.tick(start + interval));
+    assert!(!blinker.is_visible());
+
+    blinker.reset();
+    assert!(blinker.is_visible());
+
+    // the blinking period starts over from the reset
+    let reset_at = blinker.last_toggle;
+    assert!(!blinker.tick(reset_at + Duration::from_millis(499)));
+    assert!(blinker.is_visible());
+    assert!(blinker.tick(reset_at + interval));
+    assert!(!blinker.is_visible());
+}
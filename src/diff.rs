@@ -0,0 +1,169 @@
+use crate::structs::{Line, Operation, OperationType};
+
+#[derive(Debug, PartialEq)]
+enum Edit {
+    Keep,
+    Remove,
+    Add,
+}
+
+/// Compute a sequence of xi-core update operations that transforms the
+/// lines of `old` into the lines of `new`. This makes it possible to
+/// drive a [`LineCache`](struct.LineCache.html) without xi-core, for
+/// instance in tests.
+///
+/// The diff is line based, and computed with a simple longest common
+/// subsequence algorithm, which is quadratic in the number of lines.
+pub fn diff_text(old: &str, new: &str) -> Vec<Operation> {
+    let old_lines: Vec<&str> = old.split_inclusive('\n').collect();
+    let new_lines: Vec<&str> = new.split_inclusive('\n').collect();
+
+    let mut operations: Vec<Operation> = Vec::new();
+    // line number (1-based) of the next line in the new text
+    let mut line_num = 1;
+
+    for edit in edits(&old_lines, &new_lines) {
+        let operation_type = match edit {
+            Edit::Keep => OperationType::Copy,
+            Edit::Remove => OperationType::Skip,
+            Edit::Add => OperationType::Insert,
+        };
+
+        if operations.last().map(|op| &op.operation_type) != Some(&operation_type) {
+            operations.push(Operation {
+                line_num: if operation_type == OperationType::Copy {
+                    Some(line_num)
+                } else {
+                    None
+                },
+                operation_type,
+                nb_lines: 0,
+                lines: vec![],
+            });
+        }
+
+        let op = operations.last_mut().unwrap();
+        op.nb_lines += 1;
+        match edit {
+            Edit::Keep => line_num += 1,
+            Edit::Remove => {}
+            Edit::Add => {
+                op.lines.push(Line {
+                    text: new_lines[line_num as usize - 1].to_owned(),
+                    cursor: vec![],
+                    styles: vec![],
+                    line_num: Some(line_num),
+                });
+                line_num += 1;
+            }
+        }
+    }
+    operations
+}
+
+// Compute the edits that transform `old` into `new`.
+fn edits(old: &[&str], new: &[&str]) -> Vec<Edit> {
+    // lcs[i][j] is the length of the longest common subsequence of
+    // old[i..] and new[j..]
+    let mut lcs = vec![vec![0; new.len() + 1]; old.len() + 1];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            lcs[i][j] = if old[i] == new[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let mut edits = Vec::with_capacity(old.len().max(new.len()));
+    let (mut i, mut j) = (0, 0);
+    while i < old.len() && j < new.len() {
+        if old[i] == new[j] {
+            edits.push(Edit::Keep);
+            i += 1;
+            j += 1;
+        } else if lcs[i + 1][j] >= lcs[i][j + 1] {
+            edits.push(Edit::Remove);
+            i += 1;
+        } else {
+            edits.push(Edit::Add);
+            j += 1;
+        }
+    }
+    edits.extend((i..old.len()).map(|_| Edit::Remove));
+    edits.extend((j..new.len()).map(|_| Edit::Add));
+    edits
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::structs::{Update, ViewId};
+    use crate::LineCache;
+
+    fn apply(cache: &mut LineCache, old: &str, new: &str) {
+        cache.update(Update {
+            rev: None,
+            operations: diff_text(old, new),
+            pristine: false,
+            view_id: ViewId(1),
+        });
+    }
+
+    fn check_diff(old: &str, new: &str) {
+        let mut cache = LineCache::default();
+        apply(&mut cache, "", old);
+        apply(&mut cache, old, new);
+
+        let texts: Vec<&str> = cache.lines().iter().map(|l| l.text.as_str()).collect();
+        assert_eq!(texts, new.lines().collect::<Vec<_>>());
+        let line_nums: Vec<Option<u64>> = cache.lines().iter().map(|l| l.line_num).collect();
+        let expected: Vec<Option<u64>> = (1..=texts.len() as u64).map(Some).collect();
+        assert_eq!(line_nums, expected);
+        assert_eq!(cache.height(), texts.len() as u64);
+    }
+
+    #[test]
+    fn diff_operations() {
+        let ops = diff_text("a\nb\nc\n", "a\nx\nc\nd\n");
+        let summary: Vec<(OperationType, u64, Option<u64>)> = ops
+            .iter()
+            .map(|op| (op.operation_type.clone(), op.nb_lines, op.line_num))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (OperationType::Copy, 1, Some(1)),
+                (OperationType::Skip, 1, None),
+                (OperationType::Insert, 1, None),
+                (OperationType::Copy, 1, Some(3)),
+                (OperationType::Insert, 1, None),
+            ]
+        );
+        assert_eq!(ops[2].lines[0].text, "x\n");
+        assert_eq!(ops[2].lines[0].line_num, Some(2));
+        assert_eq!(ops[4].lines[0].text, "d\n");
+        assert_eq!(ops[4].lines[0].line_num, Some(4));
+    }
+
+    #[test]
+    fn diff_identical() {
+        let ops = diff_text("a\nb\n", "a\nb\n");
+        assert_eq!(ops.len(), 1);
+        assert_eq!(ops[0].operation_type, OperationType::Copy);
+        assert_eq!(ops[0].nb_lines, 2);
+        check_diff("a\nb\n", "a\nb\n");
+    }
+
+    #[test]
+    fn diff_applied_to_cache() {
+        check_diff("", "");
+        check_diff("", "a\nb\n");
+        check_diff("a\nb\n", "");
+        check_diff("a\nb\nc\n", "a\nx\nc\nd\n");
+        check_diff("a\nb\nc\nd\ne\n", "e\nd\nc\nb\na\n");
+        check_diff("fn main() {\n}\n", "fn main() {\n    println!();\n}");
+        check_diff("one\ntwo\nthree", "zero\none\nthree\nfour");
+    }
+}
@@ -10,6 +10,8 @@ pub enum ClientError {
     NotifyFailed,
     /// A request failed due to an internal error.
     RequestFailed,
+    /// A request was not sent because the connection to the core is closed.
+    Disconnected,
 
     /// A request or a notification could not be sent due to a
     /// serialization error.
@@ -20,6 +22,10 @@ pub enum ClientError {
 
     /// We failed to spawn xi-core, e.g. because it's not installed, the binary is faulty, etc.
     CoreSpawnFailed(IoError),
+
+    /// The parameters of a request or notification were rejected before
+    /// being sent.
+    InvalidInput(String),
 }
 
 impl fmt::Display for ClientError {
@@ -29,6 +35,7 @@ impl fmt::Display for ClientError {
             ClientError::RequestFailed => {
                 write!(f, "Failed to send a request, or receive its response")
             }
+            ClientError::Disconnected => write!(f, "The connection to the core is closed"),
             ClientError::ErrorReturned(ref value) => {
                 write!(f, "The core returned an error: {:?}", value)
             }
@@ -38,6 +45,7 @@ impl fmt::Display for ClientError {
             ClientError::CoreSpawnFailed(ref s) => {
                 write!(f, "Failed to spawn xi-core due to error: {}", s)
             }
+            ClientError::InvalidInput(ref s) => write!(f, "Invalid input: {}", s),
         }
     }
 }
@@ -47,9 +55,11 @@ impl error::Error for ClientError {
         match *self {
             ClientError::NotifyFailed => "Failed to send a notification",
             ClientError::RequestFailed => "Failed to send a request or receive its response",
+            ClientError::Disconnected => "The connection to the core is closed",
             ClientError::ErrorReturned(_) => "The core answered with an error",
             ClientError::SerializeFailed(_) => "Failed to serialize message",
             ClientError::CoreSpawnFailed(_) => "Failed to spawn xi-core",
+            ClientError::InvalidInput(_) => "Invalid input",
         }
     }
 
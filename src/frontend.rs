@@ -1,5 +1,7 @@
 use crate::client::Client;
-use crate::protocol::{Client as InnerClient, IntoStaticFuture, Service, ServiceBuilder};
+use crate::protocol::{
+    Client as InnerClient, IntoStaticFuture, Notification, Service, ServiceBuilder,
+};
 use crate::structs::{
     Alert, AvailableLanguages, AvailablePlugins, AvailableThemes, ConfigChanged, FindStatus,
     LanguageChanged, MeasureWidth, PluginStarted, PluginStoped, ReplaceStatus, ScrollTo, Style,
@@ -12,7 +14,7 @@ use futures::{
 use serde_json::{from_value, to_value, Value};
 
 /// Represents all possible RPC messages recieved from xi-core.
-#[derive(Debug)]
+#[derive(Debug, PartialEq, Clone)]
 pub enum XiNotification {
     Update(Update),
     ScrollTo(ScrollTo),
@@ -31,6 +33,46 @@ pub enum XiNotification {
     LanguageChanged(LanguageChanged),
 }
 
+impl<'a> From<&'a XiNotification> for Notification {
+    fn from(notification: &'a XiNotification) -> Self {
+        use XiNotification::*;
+        // None of the notification types contain maps with non-string
+        // keys, so serializing them cannot fail.
+        let (method, params) = match *notification {
+            Update(ref update) => ("update", to_value(update)),
+            ScrollTo(ref scroll_to) => ("scroll_to", to_value(scroll_to)),
+            DefStyle(ref style) => ("def_style", to_value(style)),
+            AvailablePlugins(ref plugins) => ("available_plugins", to_value(plugins)),
+            UpdateCmds(ref cmds) => ("update_cmds", to_value(cmds)),
+            PluginStarted(ref plugin) => ("plugin_started", to_value(plugin)),
+            PluginStoped(ref plugin) => ("plugin_stoped", to_value(plugin)),
+            ConfigChanged(ref config) => ("config_changed", to_value(config)),
+            ThemeChanged(ref theme) => ("theme_changed", to_value(theme)),
+            Alert(ref alert) => ("alert", to_value(alert)),
+            AvailableThemes(ref themes) => ("available_themes", to_value(themes)),
+            FindStatus(ref status) => ("find_status", to_value(status)),
+            ReplaceStatus(ref status) => ("replace_status", to_value(status)),
+            AvailableLanguages(ref langs) => ("available_languages", to_value(langs)),
+            LanguageChanged(ref lang) => ("language_changed", to_value(lang)),
+        };
+        Notification::new(method, params.expect("failed to serialize notification"))
+    }
+}
+
+impl Notification {
+    /// Deserialize the notification into an `XiNotification`. This fails
+    /// if the method is unknown or if the parameters are invalid.
+    pub fn into_xi_notification(self) -> Result<XiNotification, serde_json::Error> {
+        let Notification { method, params } = self;
+        decode_notification(&method, params).unwrap_or_else(|| {
+            Err(serde::de::Error::custom(format!(
+                "unknown notification method \"{}\"",
+                method
+            )))
+        })
+    }
+}
+
 /// The `Frontend` trait must be implemented by clients. It defines how the
 /// client handles notifications and requests coming from `xi-core`.
 pub trait Frontend {
@@ -106,163 +148,183 @@ impl<F: Frontend + Send> Service for F {
         }
     }
 
-    #[allow(clippy::cognitive_complexity)]
     fn handle_notification(&mut self, method: &str, params: Value) -> Self::NotificationFuture {
         info!("<<< notification: method={}, params={}", method, &params);
-        match method {
-            "update" => match from_value::<Update>(params) {
-                Ok(update) => Either::A(
-                    self.handle_notification(XiNotification::Update(update))
-                        .into_static_future(),
-                ),
-                Err(e) => {
-                    error!("received invalid update notification: {:?}", e);
-                    Either::B(future::err(()))
-                }
-            },
+        match decode_notification(method, params) {
+            Some(Ok(notification)) => {
+                Either::A(self.handle_notification(notification).into_static_future())
+            }
+            Some(Err(e)) => {
+                error!("received invalid {} notification: {:?}", method, e);
+                Either::B(future::err(()))
+            }
+            None => Either::B(future::err(())),
+        }
+    }
+}
 
-            "scroll_to" => match from_value::<ScrollTo>(params) {
-                Ok(scroll_to) => Either::A(
-                    self.handle_notification(XiNotification::ScrollTo(scroll_to))
-                        .into_static_future(),
-                ),
-                Err(e) => {
-                    error!("received invalid scroll_to notification: {:?}", e);
-                    Either::B(future::err(()))
-                }
-            },
+type NotificationDecoder = fn(Value) -> Result<XiNotification, serde_json::Error>;
 
-            "def_style" => match from_value::<Style>(params) {
-                Ok(style) => Either::A(
-                    self.handle_notification(XiNotification::DefStyle(style))
-                        .into_static_future(),
-                ),
-                Err(e) => {
-                    error!("received invalid def_style notification: {:?}", e);
-                    Either::B(future::err(()))
-                }
-            },
-            "available_plugins" => match from_value::<AvailablePlugins>(params) {
-                Ok(plugins) => Either::A(
-                    self.handle_notification(XiNotification::AvailablePlugins(plugins))
-                        .into_static_future(),
-                ),
-                Err(e) => {
-                    error!("received invalid available_plugins notification: {:?}", e);
-                    Either::B(future::err(()))
-                }
-            },
-            "plugin_started" => match from_value::<PluginStarted>(params) {
-                Ok(plugin) => Either::A(
-                    self.handle_notification(XiNotification::PluginStarted(plugin))
-                        .into_static_future(),
-                ),
-                Err(e) => {
-                    error!("received invalid plugin_started notification: {:?}", e);
-                    Either::B(future::err(()))
-                }
-            },
-            "plugin_stoped" => match from_value::<PluginStoped>(params) {
-                Ok(plugin) => Either::A(
-                    self.handle_notification(XiNotification::PluginStoped(plugin))
-                        .into_static_future(),
-                ),
-                Err(e) => {
-                    error!("received invalid plugin_stoped notification: {:?}", e);
-                    Either::B(future::err(()))
-                }
-            },
-            "update_cmds" => match from_value::<UpdateCmds>(params) {
-                Ok(cmds) => Either::A(
-                    self.handle_notification(XiNotification::UpdateCmds(cmds))
-                        .into_static_future(),
-                ),
-                Err(e) => {
-                    error!("received invalid update_cmds notification: {:?}", e);
-                    Either::B(future::err(()))
-                }
-            },
-            "config_changed" => match from_value::<ConfigChanged>(params) {
-                Ok(config) => Either::A(
-                    self.handle_notification(XiNotification::ConfigChanged(config))
-                        .into_static_future(),
-                ),
-                Err(e) => {
-                    error!("received invalid config_changed notification: {:?}", e);
-                    Either::B(future::err(()))
-                }
-            },
-            "theme_changed" => match from_value::<ThemeChanged>(params) {
-                Ok(theme) => Either::A(
-                    self.handle_notification(XiNotification::ThemeChanged(theme))
-                        .into_static_future(),
-                ),
-                Err(e) => {
-                    error!("received invalid theme_changed notification: {:?}", e);
-                    Either::B(future::err(()))
-                }
-            },
-            "alert" => match from_value::<Alert>(params) {
-                Ok(alert) => Either::A(
-                    self.handle_notification(XiNotification::Alert(alert))
-                        .into_static_future(),
-                ),
-                Err(e) => {
-                    error!("received invalid alert notification: {:?}", e);
-                    Either::B(future::err(()))
-                }
-            },
-            "available_themes" => match from_value::<AvailableThemes>(params) {
-                Ok(themes) => Either::A(
-                    self.handle_notification(XiNotification::AvailableThemes(themes))
-                        .into_static_future(),
-                ),
-                Err(e) => {
-                    error!("received invalid available_themes notification: {:?}", e);
-                    Either::B(future::err(()))
-                }
-            },
-            "find_status" => match from_value::<FindStatus>(params) {
-                Ok(find_status) => Either::A(
-                    self.handle_notification(XiNotification::FindStatus(find_status))
-                        .into_static_future(),
-                ),
-                Err(e) => {
-                    error!("received invalid find_status notification: {:?}", e);
-                    Either::B(future::err(()))
-                }
-            },
-            "replace_status" => match from_value::<ReplaceStatus>(params) {
-                Ok(replace_status) => Either::A(
-                    self.handle_notification(XiNotification::ReplaceStatus(replace_status))
-                        .into_static_future(),
-                ),
-                Err(e) => {
-                    error!("received invalid replace_status notification: {:?}", e);
-                    Either::B(future::err(()))
-                }
-            },
-            "available_languages" => match from_value::<AvailableLanguages>(params) {
-                Ok(available_langs) => Either::A(
-                    self.handle_notification(XiNotification::AvailableLanguages(available_langs))
-                        .into_static_future(),
-                ),
-                Err(e) => {
-                    error!("received invalid available_languages notification: {:?}", e);
-                    Either::B(future::err(()))
-                }
-            },
-            "language_changed" => match from_value::<LanguageChanged>(params) {
-                Ok(lang) => Either::A(
-                    self.handle_notification(XiNotification::LanguageChanged(lang))
-                        .into_static_future(),
-                ),
-                Err(e) => {
-                    error!("received invalid language_changed notification: {:?}", e);
-                    Either::B(future::err(()))
-                }
-            },
-            _ => Either::B(future::err(())),
+/// Deserializers for the notifications sent by xi-core, sorted by method
+/// name so that they can be looked up with a binary search.
+static NOTIFICATION_DECODERS: [(&str, NotificationDecoder); 15] = [
+    ("alert", |params| {
+        from_value(params).map(XiNotification::Alert)
+    }),
+    ("available_languages", |params| {
+        from_value(params).map(XiNotification::AvailableLanguages)
+    }),
+    ("available_plugins", |params| {
+        from_value(params).map(XiNotification::AvailablePlugins)
+    }),
+    ("available_themes", |params| {
+        from_value(params).map(XiNotification::AvailableThemes)
+    }),
+    ("config_changed", |params| {
+        from_value(params).map(XiNotification::ConfigChanged)
+    }),
+    ("def_style", |params| {
+        from_value(params).map(XiNotification::DefStyle)
+    }),
+    ("find_status", |params| {
+        from_value(params).map(XiNotification::FindStatus)
+    }),
+    ("language_changed", |params| {
+        from_value(params).map(XiNotification::LanguageChanged)
+    }),
+    ("plugin_started", |params| {
+        from_value(params).map(XiNotification::PluginStarted)
+    }),
+    ("plugin_stoped", |params| {
+        from_value(params).map(XiNotification::PluginStoped)
+    }),
+    ("replace_status", |params| {
+        from_value(params).map(XiNotification::ReplaceStatus)
+    }),
+    ("scroll_to", |params| {
+        from_value(params).map(XiNotification::ScrollTo)
+    }),
+    ("theme_changed", |params| {
+        from_value(params).map(XiNotification::ThemeChanged)
+    }),
+    ("update", |params| {
+        from_value(params).map(XiNotification::Update)
+    }),
+    ("update_cmds", |params| {
+        from_value(params).map(XiNotification::UpdateCmds)
+    }),
+];
+
+/// Deserialize the parameters of a notification. Return `None` if the
+/// method is unknown.
+fn decode_notification(
+    method: &str,
+    params: Value,
+) -> Option<Result<XiNotification, serde_json::Error>> {
+    NOTIFICATION_DECODERS
+        .binary_search_by_key(&method, |&(name, _)| name)
+        .ok()
+        .map(|index| (NOTIFICATION_DECODERS[index].1)(params))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::structs::{Line, Operation, OperationType, ViewId};
+
+    #[test]
+    fn notification_from_update() {
+        let update = Update {
+            rev: None,
+            operations: vec![Operation {
+                operation_type: OperationType::Insert,
+                nb_lines: 1,
+                line_num: None,
+                lines: vec![Line {
+                    text: "foo".to_owned(),
+                    cursor: vec![0],
+                    styles: vec![],
+                    line_num: Some(1),
+                }],
+            }],
+            pristine: true,
+            view_id: ViewId(1),
+        };
+        let notification = Notification::from(&XiNotification::Update(update.clone()));
+        assert_eq!(notification.method, "update");
+        assert_eq!(from_value::<Update>(notification.params).unwrap(), update);
+    }
+
+    #[test]
+    fn notification_from_scroll_to() {
+        let scroll_to = ScrollTo {
+            line: 2,
+            column: 3,
+            view_id: ViewId(1),
+        };
+        let notification = Notification::from(&XiNotification::ScrollTo(scroll_to));
+        assert_eq!(
+            notification,
+            Notification::new(
+                "scroll_to",
+                json!({"line": 2, "col": 3, "view_id": "view-id-1"})
+            )
+        );
+    }
+
+    #[test]
+    fn notification_from_def_style() {
+        let style = Style {
+            id: 2,
+            fg_color: Some(0xff00_00ff),
+            ..Style::default()
+        };
+        let notification = Notification::from(&XiNotification::DefStyle(style.clone()));
+        assert_eq!(notification.method, "def_style");
+        assert_eq!(from_value::<Style>(notification.params).unwrap(), style);
+    }
+
+    #[test]
+    fn notification_decoders_are_sorted() {
+        assert!(NOTIFICATION_DECODERS
+            .windows(2)
+            .all(|pair| pair[0].0 < pair[1].0));
+    }
+
+    #[test]
+    fn decode_notification_by_method() {
+        let params = json!({"line": 2, "col": 3, "view_id": "view-id-1"});
+        match decode_notification("scroll_to", params) {
+            Some(Ok(XiNotification::ScrollTo(scroll_to))) => assert_eq!(scroll_to.line, 2),
+            other => panic!("expected a scroll_to notification, got {:?}", other),
         }
+        assert!(decode_notification("scroll_to", json!({}))
+            .unwrap()
+            .is_err());
+        assert!(decode_notification("unknown", json!({})).is_none());
+    }
+
+    #[test]
+    fn notification_into_xi_notification() {
+        let scroll_to = ScrollTo {
+            line: 2,
+            column: 3,
+            view_id: ViewId(1),
+        };
+        let notification = XiNotification::ScrollTo(scroll_to);
+        assert_eq!(
+            Notification::from(&notification)
+                .into_xi_notification()
+                .unwrap(),
+            notification
+        );
+
+        let err = Notification::new("unknown", json!({}))
+            .into_xi_notification()
+            .unwrap_err();
+        assert!(err.to_string().contains("unknown notification method"));
+        assert!(Notification::new("scroll_to", json!({}))
+            .into_xi_notification()
+            .is_err());
     }
 }
@@ -0,0 +1,59 @@
+/// Formatter for the line numbers displayed in a gutter.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct LineNumbers {
+    /// Width of the gutter, in characters.
+    pub width: usize,
+    /// Value added to each line number before it is displayed.
+    pub offset: u64,
+}
+
+impl LineNumbers {
+    /// Format a line number, right aligned in a string of `self.width`
+    /// characters. Lines without a line number (ie lines that continue a
+    /// wrapped line) are rendered as blank.
+    pub fn format(&self, line_num: Option<u64>) -> String {
+        match line_num {
+            Some(num) => format!("{:>width$}", num + self.offset, width = self.width),
+            None => " ".repeat(self.width),
+        }
+    }
+
+    /// Compute the minimum gutter width needed to display the line
+    /// numbers of a document with `total_lines` lines.
+    pub fn required_width(total_lines: u64) -> usize {
+        let mut width = 1;
+        let mut n = total_lines;
+        while n >= 10 {
+            n /= 10;
+            width += 1;
+        }
+        width
+    }
+}
+
+#[test]
+fn format_line_numbers() {
+    let line_numbers = LineNumbers {
+        width: 4,
+        offset: 0,
+    };
+    assert_eq!(line_numbers.format(Some(7)), "   7");
+    assert_eq!(line_numbers.format(Some(1234)), "1234");
+    assert_eq!(line_numbers.format(None), "    ");
+
+    let line_numbers = LineNumbers {
+        width: 3,
+        offset: 10,
+    };
+    assert_eq!(line_numbers.format(Some(5)), " 15");
+}
+
+#[test]
+fn required_width() {
+    assert_eq!(LineNumbers::required_width(0), 1);
+    assert_eq!(LineNumbers::required_width(9), 1);
+    assert_eq!(LineNumbers::required_width(10), 2);
+    assert_eq!(LineNumbers::required_width(999), 3);
+    assert_eq!(LineNumbers::required_width(1000), 4);
+    assert_eq!(LineNumbers::required_width(u64::MAX), 20);
+}
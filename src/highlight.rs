@@ -0,0 +1,210 @@
+use std::collections::HashMap;
+use std::fmt;
+
+use crate::structs::{Line, Style};
+
+/// A line of text along with the styles defined by xi-core, that can be
+/// displayed with ANSI escape sequences:
+///
+/// ```rust,ignore
+/// println!("{}", HighlightedText::new(&line, &styles));
+/// ```
+#[derive(Debug)]
+pub struct HighlightedText<'a> {
+    line: &'a Line,
+    styles: &'a HashMap<u64, Style>,
+}
+
+impl<'a> HighlightedText<'a> {
+    /// `styles` are the styles received via `def_style` notifications,
+    /// indexed by their id.
+    pub fn new(line: &'a Line, styles: &'a HashMap<u64, Style>) -> Self {
+        HighlightedText { line, styles }
+    }
+}
+
+// Return the escape sequences that enable the given style.
+fn escape_sequence(style: &Style) -> String {
+    let mut seq = String::new();
+    if let Some(color) = style.fg_color {
+        seq.push_str(&format!(
+            "\x1b[38;2;{};{};{}m",
+            (color >> 16) & 0xff,
+            (color >> 8) & 0xff,
+            color & 0xff
+        ));
+    }
+    if let Some(color) = style.bg_color {
+        seq.push_str(&format!(
+            "\x1b[48;2;{};{};{}m",
+            (color >> 16) & 0xff,
+            (color >> 8) & 0xff,
+            color & 0xff
+        ));
+    }
+    if style.weight.unwrap_or(0) >= 700 {
+        seq.push_str("\x1b[1m");
+    }
+    if style.italic == Some(true) {
+        seq.push_str("\x1b[3m");
+    }
+    if style.underline == Some(true) {
+        seq.push_str("\x1b[4m");
+    }
+    seq
+}
+
+// Clamp `offset` to `[min, text.len()]` and move it back to the closest
+// char boundary.
+fn clamp_offset(text: &str, offset: i64, min: usize) -> usize {
+    let mut offset = offset.max(min as i64).min(text.len() as i64) as usize;
+    while !text.is_char_boundary(offset) {
+        offset -= 1;
+    }
+    offset.max(min)
+}
+
+impl<'a> fmt::Display for HighlightedText<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let text = &self.line.text;
+        // Number of bytes of `text` written so far
+        let mut written = 0;
+        // The style offsets are relative to the end of the previous span
+        let mut span_end: i64 = 0;
+
+        for style_def in &self.line.styles {
+            let start = span_end + style_def.offset;
+            span_end = start + style_def.length as i64;
+
+            // Overlapping spans are not supported: the part that overlaps
+            // the previous span is not styled again.
+            let start = clamp_offset(text, start, written);
+            let end = clamp_offset(text, span_end, start);
+            f.write_str(&text[written..start])?;
+
+            let seq = self
+                .styles
+                .get(&style_def.style_id)
+                .map(escape_sequence)
+                .unwrap_or_default();
+            if seq.is_empty() || start == end {
+                f.write_str(&text[start..end])?;
+            } else {
+                write!(f, "{}{}\x1b[0m", seq, &text[start..end])?;
+            }
+            written = end;
+        }
+        f.write_str(&text[written..])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::structs::StyleDef;
+
+    fn styles() -> HashMap<u64, Style> {
+        let mut styles = HashMap::new();
+        styles.insert(
+            2,
+            Style {
+                id: 2,
+                fg_color: Some(0xff_ff_00_00),
+                weight: Some(700),
+                ..Style::default()
+            },
+        );
+        styles.insert(
+            3,
+            Style {
+                id: 3,
+                bg_color: Some(0xff_00_80_ff),
+                italic: Some(true),
+                underline: Some(true),
+                ..Style::default()
+            },
+        );
+        styles
+    }
+
+    fn line(text: &str, styles: Vec<StyleDef>) -> Line {
+        Line {
+            text: text.to_owned(),
+            cursor: vec![],
+            styles,
+            line_num: Some(1),
+        }
+    }
+
+    #[test]
+    fn display_unstyled_line() {
+        let line = line("fn main() {}", vec![]);
+        assert_eq!(
+            HighlightedText::new(&line, &styles()).to_string(),
+            "fn main() {}"
+        );
+    }
+
+    #[test]
+    fn display_styled_line() {
+        let line = line(
+            "fn main() {}",
+            vec![
+                StyleDef {
+                    offset: 0,
+                    length: 2,
+                    style_id: 2,
+                },
+                StyleDef {
+                    offset: 1,
+                    length: 4,
+                    style_id: 3,
+                },
+            ],
+        );
+        assert_eq!(
+            HighlightedText::new(&line, &styles()).to_string(),
+            "\x1b[38;2;255;0;0m\x1b[1mfn\x1b[0m \
+             \x1b[48;2;0;128;255m\x1b[3m\x1b[4mmain\x1b[0m() {}"
+        );
+    }
+
+    #[test]
+    fn display_unknown_style() {
+        let line = line(
+            "foo bar",
+            vec![StyleDef {
+                offset: 4,
+                length: 3,
+                style_id: 42,
+            }],
+        );
+        assert_eq!(
+            HighlightedText::new(&line, &styles()).to_string(),
+            "foo bar"
+        );
+    }
+
+    #[test]
+    fn display_out_of_bounds_styles() {
+        let line = line(
+            "é",
+            vec![
+                StyleDef {
+                    offset: 0,
+                    length: 1,
+                    style_id: 2,
+                },
+                StyleDef {
+                    offset: 0,
+                    length: 10,
+                    style_id: 2,
+                },
+            ],
+        );
+        assert_eq!(
+            HighlightedText::new(&line, &styles()).to_string(),
+            "\x1b[38;2;255;0;0m\x1b[1mé\x1b[0m"
+        );
+    }
+}
@@ -137,20 +137,41 @@ extern crate serde_json;
 mod cache;
 mod client;
 mod core;
+mod cursor;
+mod diff;
 mod errors;
 mod frontend;
+mod gutter;
+mod highlight;
 mod protocol;
 mod structs;
 
-pub use crate::cache::LineCache;
+pub use crate::cache::{LineCache, LineCacheIter, LineCacheSnapshot, WrappedLineCache};
 pub use crate::client::Client;
-pub use crate::core::{spawn, spawn_command, CoreStderr};
+pub use crate::core::{spawn, spawn_command, spawn_command_with_codec, CoreStderr};
+pub use crate::cursor::CursorBlinker;
+pub use crate::diff::diff_text;
 pub use crate::errors::{ClientError, ServerError};
 pub use crate::frontend::{Frontend, FrontendBuilder, XiNotification};
-pub use crate::protocol::IntoStaticFuture;
+pub use crate::gutter::LineNumbers;
+pub use crate::highlight::HighlightedText;
+pub use crate::protocol::{
+    Codec, ConnectionState, IntoStaticFuture, MessageCodec, Notification, StrictCodec,
+};
 pub use crate::structs::{
-    Alert, AvailableLanguages, AvailablePlugins, AvailableThemes, ConfigChanged, ConfigChanges,
-    FindStatus, LanguageChanged, Line, MeasureWidth, ModifySelection, Operation, OperationType,
-    PluginStarted, PluginStoped, Position, Query, ReplaceStatus, ScrollTo, Status, Style, StyleDef,
-    ThemeChanged, ThemeSettings, Update, UpdateCmds, ViewId,
+    theme_to_css, Alert, AlertSeverity, AvailableLanguages, AvailablePlugins, AvailableThemes,
+    ConfigChanged, ConfigChanges, FindQuery, FindStatus, LanguageChanged, Line, MeasureWidth,
+    ModifySelection, Operation, OperationType, PluginStarted, PluginStoped, Position, Query,
+    ReplaceStatus, ScrollTo, Status, Style, StyleDef, ThemeChanged, ThemeSettings, Update,
+    UpdateCmds, ViewId,
 };
+
+/// Return the version of this crate, as found in its `Cargo.toml`.
+/// This can be used by frontends for logging and debugging purposes.
+///
+/// ```rust
+/// assert!(!xrl::version().is_empty());
+/// ```
+pub fn version() -> &'static str {
+    env!("CARGO_PKG_VERSION")
+}
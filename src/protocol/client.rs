@@ -1,11 +1,13 @@
 use std::collections::HashMap;
 use std::io;
+use std::sync::{Arc, Mutex};
 
 use futures::sync::{mpsc, oneshot};
 use futures::{Async, Future, Poll, Stream};
 use serde_json::Value;
 use tokio::io::{AsyncRead, AsyncWrite};
 
+use super::codec::MessageCodec;
 use super::errors::RpcError;
 use super::message::Response as ResponseMessage;
 use super::message::{Message, Notification, Request};
@@ -19,17 +21,37 @@ type NotificationRx = mpsc::UnboundedReceiver<(Notification, AckTx)>;
 type ResponseTx = oneshot::Sender<Result<Value, Value>>;
 type AckTx = oneshot::Sender<()>;
 
+/// Lifecycle of the connection between a `Client` and its Xi-RPC endpoint.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConnectionState {
+    /// The endpoint has been created but has not been polled yet.
+    Initializing,
+    /// The endpoint is running.
+    Ready,
+    /// The endpoint is shutting down.
+    ShuttingDown,
+    /// The endpoint has been dropped. Messages can't be sent anymore.
+    Closed,
+}
+
+type SharedState = Arc<Mutex<ConnectionState>>;
+
 /// Future response to a request. It resolved once the response is available.
-pub struct Response(oneshot::Receiver<Result<Value, Value>>);
+/// It resolves to `RpcError::Disconnected` if the request was made after the
+/// connection was closed.
+pub struct Response(Option<oneshot::Receiver<Result<Value, Value>>>);
 
 impl Future for Response {
     type Item = Result<Value, Value>;
     type Error = RpcError;
 
     fn poll(&mut self) -> Poll<Self::Item, Self::Error> {
-        self.0
-            .poll()
-            .map_err(|oneshot::Canceled| RpcError::ResponseCanceled)
+        match self.0 {
+            Some(ref mut rx) => rx
+                .poll()
+                .map_err(|oneshot::Canceled| RpcError::ResponseCanceled),
+            None => Err(RpcError::Disconnected),
+        }
     }
 }
 
@@ -50,6 +72,8 @@ impl Future for Ack {
 }
 
 pub struct InnerClient {
+    state: SharedState,
+    started: bool,
     shutting_down: bool,
     request_id: u64,
     requests_rx: RequestRx,
@@ -64,10 +88,13 @@ impl InnerClient {
         let (requests_tx, requests_rx) = mpsc::unbounded();
         let (notifications_tx, notifications_rx) = mpsc::unbounded();
         let (shutdown_tx, shutdown_rx) = mpsc::unbounded();
+        let state = Arc::new(Mutex::new(ConnectionState::Initializing));
 
-        let client_proxy = Client::new(requests_tx, notifications_tx, shutdown_tx);
+        let client_proxy = Client::new(requests_tx, notifications_tx, shutdown_tx, state.clone());
 
         let client = InnerClient {
+            state,
+            started: false,
             shutting_down: false,
             request_id: 0,
             requests_rx,
@@ -80,9 +107,27 @@ impl InnerClient {
         (client, client_proxy)
     }
 
+    fn set_state(&self, state: ConnectionState) {
+        *self.state.lock().unwrap() = state;
+    }
+
+    /// Mark the connection as ready, unless it is already shutting down.
+    /// Only the first call has an effect.
+    pub fn start(&mut self) {
+        if self.started {
+            return;
+        }
+        self.started = true;
+        let mut state = self.state.lock().unwrap();
+        if *state == ConnectionState::Initializing {
+            *state = ConnectionState::Ready;
+        }
+    }
+
     pub fn shutdown(&mut self) {
         debug!("shutting down inner client");
         self.shutting_down = true;
+        self.set_state(ConnectionState::ShuttingDown);
     }
 
     pub fn is_shutting_down(&self) -> bool {
@@ -120,7 +165,10 @@ impl InnerClient {
         }
     }
 
-    pub fn process_notifications<T: AsyncRead + AsyncWrite>(&mut self, stream: &mut Transport<T>) {
+    pub fn process_notifications<T: AsyncRead + AsyncWrite, C: MessageCodec>(
+        &mut self,
+        stream: &mut Transport<T, C>,
+    ) {
         trace!("polling client notifications channel");
         loop {
             match self.notifications_rx.poll() {
@@ -148,7 +196,10 @@ impl InnerClient {
         }
     }
 
-    pub fn process_requests<T: AsyncRead + AsyncWrite>(&mut self, stream: &mut Transport<T>) {
+    pub fn process_requests<T: AsyncRead + AsyncWrite, C: MessageCodec>(
+        &mut self,
+        stream: &mut Transport<T, C>,
+    ) {
         trace!("polling client requests channel");
         loop {
             match self.requests_rx.poll() {
@@ -202,6 +253,12 @@ impl InnerClient {
     }
 }
 
+impl Drop for InnerClient {
+    fn drop(&mut self) {
+        self.set_state(ConnectionState::Closed);
+    }
+}
+
 /// `Client` can be used to send Xi-RPC requests and notifications. It
 /// implements `Clone` so multiple clients can be instantiated. When
 /// all the `Client` instances are dropped, the Xi-RPC endoint shuts
@@ -214,6 +271,7 @@ pub struct Client {
     requests_tx: RequestTx,
     notifications_tx: NotificationTx,
     shutdown_tx: mpsc::UnboundedSender<()>,
+    state: SharedState,
 }
 
 impl Client {
@@ -221,20 +279,32 @@ impl Client {
         requests_tx: RequestTx,
         notifications_tx: NotificationTx,
         shutdown_tx: mpsc::UnboundedSender<()>,
+        state: SharedState,
     ) -> Self {
         Client {
             requests_tx,
             notifications_tx,
             shutdown_tx,
+            state,
         }
     }
 
+    /// Return the current state of the connection to the Xi-RPC endpoint.
+    pub fn state(&self) -> ConnectionState {
+        *self.state.lock().unwrap()
+    }
+
+    /// Send a request. If the connection is closed, the returned future
+    /// resolves immediately to `RpcError::Disconnected`.
     pub fn request(&self, method: &str, params: Value) -> Response {
         trace!(
             "forwarding request to endpoint (method={}, params={:?})",
             method,
             params
         );
+        if self.state() == ConnectionState::Closed {
+            return Response(None);
+        }
         let request = Request {
             id: 0,
             method: method.to_owned(),
@@ -246,7 +316,7 @@ impl Client {
         // rx will return Canceled when polled. In turn, that is translated
         // into a BrokenPipe, which conveys the proper error.
         let _ = mpsc::UnboundedSender::unbounded_send(&self.requests_tx, (request, tx));
-        Response(rx)
+        Response(Some(rx))
     }
 
     pub fn notify(&self, method: &str, params: Value) -> Ack {
@@ -255,10 +325,7 @@ impl Client {
             method,
             params
         );
-        let notification = Notification {
-            method: method.to_owned(),
-            params,
-        };
+        let notification = Notification::new(method, params);
         let (tx, rx) = oneshot::channel();
         let _ = mpsc::UnboundedSender::unbounded_send(&self.notifications_tx, (notification, tx));
         Ack(rx)
@@ -280,3 +347,27 @@ impl Future for Client {
         Ok(Async::Ready(()))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_connection_state() {
+        let (mut inner, client) = InnerClient::new();
+        assert_eq!(client.state(), ConnectionState::Initializing);
+        inner.start();
+        assert_eq!(client.state(), ConnectionState::Ready);
+        inner.shutdown();
+        assert_eq!(client.state(), ConnectionState::ShuttingDown);
+        inner.start();
+        assert_eq!(client.state(), ConnectionState::ShuttingDown);
+        drop(inner);
+        assert_eq!(client.state(), ConnectionState::Closed);
+
+        match client.request("new_view", json!({})).wait() {
+            Err(RpcError::Disconnected) => {}
+            other => panic!("expected RpcError::Disconnected, got {:?}", other),
+        }
+    }
+}
@@ -1,23 +1,51 @@
 use bytes::{BufMut, BytesMut};
+use std::convert::TryFrom;
 use std::io;
 use tokio_codec::{Decoder, Encoder};
 
 use super::errors::DecodeError;
 use super::message::Message;
 
+/// A codec that can frame Xi-RPC messages. This is implemented for
+/// all the types that can both encode and decode `Message`s, like
+/// [`Codec`] and [`StrictCodec`].
+pub trait MessageCodec:
+    Decoder<Item = Message, Error = io::Error> + Encoder<Item = Message, Error = io::Error>
+{
+}
+
+impl<C> MessageCodec for C where
+    C: Decoder<Item = Message, Error = io::Error> + Encoder<Item = Message, Error = io::Error>
+{
+}
+
+/// The default codec. Lines that cannot be decoded are discarded.
 pub struct Codec;
 
+/// A codec that returns an error for lines that cannot be decoded,
+/// instead of discarding them.
+pub struct StrictCodec;
+
+// Split the next line out of the buffer, without the trailing '\n' or
+// "\r\n"
+fn next_line(buf: &mut BytesMut) -> Option<BytesMut> {
+    let n = buf.as_ref().iter().position(|b| *b == b'\n')?;
+    let mut line = buf.split_to(n);
+    buf.split_to(1); // remove the '\n'
+    if line.as_ref().last() == Some(&b'\r') {
+        line.truncate(n - 1);
+    }
+    trace!("<<< {}", String::from_utf8_lossy(&line));
+    Some(line)
+}
+
 impl Decoder for Codec {
     type Item = Message;
     type Error = io::Error;
 
     fn decode(&mut self, buf: &mut BytesMut) -> io::Result<Option<Self::Item>> {
-        if let Some(n) = buf.as_ref().iter().position(|b| *b == b'\n') {
-            let line = buf.split_to(n);
-            trace!("<<< {}", ::std::str::from_utf8(&line).unwrap());
-            buf.split_to(1); // remove the '\n'
-
-            match Message::decode(&mut io::Cursor::new(&line)) {
+        if let Some(line) = next_line(buf) {
+            match Message::try_from(&line[..]) {
                 Ok(message) => return Ok(Some(message)),
                 Err(err) => match err {
                     DecodeError::Io(err) => return Err(err),
@@ -42,3 +70,162 @@ impl Encoder for Codec {
         Ok(())
     }
 }
+
+impl Decoder for StrictCodec {
+    type Item = Message;
+    type Error = io::Error;
+
+    fn decode(&mut self, buf: &mut BytesMut) -> io::Result<Option<Self::Item>> {
+        if let Some(line) = next_line(buf) {
+            return match Message::try_from(&line[..]) {
+                Ok(message) => Ok(Some(message)),
+                Err(DecodeError::Io(err)) => Err(err),
+                Err(err) => Err(io::Error::new(io::ErrorKind::InvalidData, err)),
+            };
+        }
+        Ok(None)
+    }
+}
+
+impl Encoder for StrictCodec {
+    type Item = Message;
+    type Error = io::Error;
+
+    fn encode(&mut self, msg: Self::Item, buf: &mut BytesMut) -> io::Result<()> {
+        Codec.encode(msg, buf)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::message::Notification;
+
+    fn decode_error(codec: &mut StrictCodec, input: &[u8]) -> DecodeError {
+        let mut buf = BytesMut::from(input);
+        let err = codec.decode(&mut buf).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        *err.into_inner().unwrap().downcast::<DecodeError>().unwrap()
+    }
+
+    #[test]
+    fn next_line_strips_crlf() {
+        let mut buf = BytesMut::from(&b"{}\r\n{}\n"[..]);
+        assert_eq!(next_line(&mut buf).unwrap().as_ref(), b"{}");
+        assert_eq!(next_line(&mut buf).unwrap().as_ref(), b"{}");
+        assert!(next_line(&mut buf).is_none());
+    }
+
+    #[test]
+    fn decode_crlf_terminated_message() {
+        let mut buf = BytesMut::from(&b"{\"method\": \"update\", \"params\": {}}\r\n"[..]);
+        let msg = StrictCodec.decode(&mut buf).unwrap().unwrap();
+        assert_eq!(
+            msg,
+            Message::Notification(Notification::new("update", json!({})))
+        );
+        assert!(buf.is_empty());
+    }
+
+    #[test]
+    fn lenient_codec_discards_invalid_json() {
+        let mut buf = BytesMut::from(&b"{\"foo\": 1}\n"[..]);
+        assert!(Codec.decode(&mut buf).unwrap().is_none());
+        assert!(buf.is_empty());
+    }
+
+    #[test]
+    fn strict_codec_returns_invalid_json_error() {
+        match decode_error(&mut StrictCodec, b"{\"foo\": 1}\n") {
+            DecodeError::InvalidJson => (),
+            err => panic!("unexpected error: {:?}", err),
+        }
+        match decode_error(&mut StrictCodec, b"not json\n") {
+            DecodeError::InvalidJson => (),
+            err => panic!("unexpected error: {:?}", err),
+        }
+    }
+
+    #[test]
+    fn strict_codec_returns_truncated_error() {
+        match decode_error(&mut StrictCodec, b"{\"method\": \"foo\"\n") {
+            DecodeError::Truncated => (),
+            err => panic!("unexpected error: {:?}", err),
+        }
+    }
+
+    #[test]
+    fn strict_codec_decodes_valid_messages() {
+        let mut buf = BytesMut::from(&b"{\"method\": \"foo\", \"params\": {}}\n"[..]);
+        match StrictCodec.decode(&mut buf).unwrap() {
+            Some(Message::Notification(notification)) => assert_eq!(notification.method, "foo"),
+            msg => panic!("unexpected message: {:?}", msg),
+        }
+    }
+
+    #[test]
+    fn strict_codec_waits_for_full_line() {
+        let mut buf = BytesMut::from(&b"{\"method\": "[..]);
+        assert!(StrictCodec.decode(&mut buf).unwrap().is_none());
+        assert!(!buf.is_empty());
+    }
+
+    mod roundtrip {
+        use super::*;
+        use crate::protocol::message::{Notification, Request, Response};
+        use proptest::prelude::*;
+        use serde_json::{Map, Value};
+
+        fn arb_value() -> impl Strategy<Value = Value> {
+            let leaf = prop_oneof![
+                Just(Value::Null),
+                any::<bool>().prop_map(Value::Bool),
+                any::<i64>().prop_map(Value::from),
+                any::<u64>().prop_map(Value::from),
+                ".*".prop_map(Value::String),
+            ];
+            leaf.prop_recursive(4, 32, 8, |inner| {
+                prop_oneof![
+                    prop::collection::vec(inner.clone(), 0..8).prop_map(Value::Array),
+                    prop::collection::hash_map(".*", inner, 0..8)
+                        .prop_map(|map| Value::Object(map.into_iter().collect::<Map<_, _>>())),
+                ]
+            })
+        }
+
+        fn arb_message() -> impl Strategy<Value = Message> {
+            prop_oneof![
+                (any::<u64>(), ".*", arb_value()).prop_map(|(id, method, params)| {
+                    Message::Request(Request { id, method, params })
+                }),
+                (any::<u64>(), any::<bool>(), arb_value()).prop_map(|(id, is_ok, value)| {
+                    let result = if is_ok { Ok(value) } else { Err(value) };
+                    Message::Response(Response { id, result })
+                }),
+                (".*", arb_value()).prop_map(|(method, params)| {
+                    Message::Notification(Notification { method, params })
+                }),
+            ]
+        }
+
+        proptest! {
+            #[test]
+            fn encode_then_decode_is_identity(message in arb_message()) {
+                let mut buf = BytesMut::new();
+                Codec.encode(message.clone(), &mut buf).unwrap();
+                let decoded = Codec.decode(&mut buf).unwrap();
+                prop_assert_eq!(decoded, Some(message));
+                prop_assert!(buf.is_empty());
+            }
+
+            #[test]
+            fn strict_encode_then_decode_is_identity(message in arb_message()) {
+                let mut buf = BytesMut::new();
+                StrictCodec.encode(message.clone(), &mut buf).unwrap();
+                let decoded = StrictCodec.decode(&mut buf).unwrap();
+                prop_assert_eq!(decoded, Some(message));
+                prop_assert!(buf.is_empty());
+            }
+        }
+    }
+}
@@ -5,25 +5,29 @@ use tokio::io::{AsyncRead, AsyncWrite};
 
 use super::client::Client;
 use super::client::InnerClient;
+use super::codec::{Codec, MessageCodec};
 use super::message::Message;
 use super::server::{Server, Service, ServiceBuilder};
 use super::transport::Transport;
 
-pub struct Endpoint<S: Service, T: AsyncRead + AsyncWrite> {
-    stream: Transport<T>,
+pub struct Endpoint<S: Service, T: AsyncRead + AsyncWrite, C: MessageCodec = Codec> {
+    stream: Transport<T, C>,
     client: InnerClient,
     server: Server<S>,
 }
 
-impl<S, T> Endpoint<S, T>
+impl<S, T, C> Endpoint<S, T, C>
 where
     S: Service,
     T: AsyncRead + AsyncWrite,
+    C: MessageCodec,
 {
-    pub fn new<B: ServiceBuilder<Service = S>>(stream: T, builder: B) -> (Self, Client) {
+    /// Create a new endpoint. The given `codec` is used to frame the
+    /// messages exchanged over `stream`.
+    pub fn new<B: ServiceBuilder<Service = S>>(stream: T, builder: B, codec: C) -> (Self, Client) {
         let (client, client_proxy) = InnerClient::new();
         let endpoint = Endpoint {
-            stream: Transport::new(stream),
+            stream: Transport::new(stream, codec),
             server: Server::new(builder.build(client_proxy.clone())),
             client,
         };
@@ -40,17 +44,22 @@ where
         }
     }
 
-    fn flush(&mut self) {
+    // Flush the stream and acknowledge the notifications that have been
+    // sent. Return whether the stream has been entirely flushed.
+    fn flush(&mut self) -> bool {
         trace!("flushing stream");
         match self.stream.poll_complete() {
-            Ok(Async::Ready(())) => self.client.acknowledge_notifications(),
-            Ok(Async::NotReady) => (),
+            Ok(Async::Ready(())) => {
+                self.client.acknowledge_notifications();
+                true
+            }
+            Ok(Async::NotReady) => false,
             Err(e) => panic!("Failed to flush the sink: {:?}", e),
         }
     }
 }
 
-impl<S, T: AsyncRead + AsyncWrite> Future for Endpoint<S, T>
+impl<S, T: AsyncRead + AsyncWrite, C: MessageCodec> Future for Endpoint<S, T, C>
 where
     S: Service,
 {
@@ -58,6 +67,7 @@ where
     type Error = io::Error;
 
     fn poll(&mut self) -> Poll<Self::Item, Self::Error> {
+        self.client.start();
         trace!("polling stream");
         loop {
             match self.stream.poll()? {
@@ -94,11 +104,121 @@ where
             client_shutdown = true;
         }
 
-        self.flush();
-        if client_shutdown {
+        // Don't exit before the pending notifications are flushed,
+        // otherwise they'd be dropped and their `Ack` canceled.
+        if self.flush() && client_shutdown {
             Ok(Async::Ready(()))
         } else {
             Ok(Async::NotReady)
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::future;
+    use serde_json::Value;
+    use std::io::{Read, Write};
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::Arc;
+
+    use crate::protocol::errors::RpcError;
+
+    // A stream that never has anything to read, and that accepts writes
+    // only when `writable` is set.
+    struct MockStream {
+        writable: Arc<AtomicBool>,
+    }
+
+    impl Read for MockStream {
+        fn read(&mut self, _buf: &mut [u8]) -> io::Result<usize> {
+            Err(io::ErrorKind::WouldBlock.into())
+        }
+    }
+
+    impl Write for MockStream {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            if self.writable.load(Ordering::SeqCst) {
+                Ok(buf.len())
+            } else {
+                Err(io::ErrorKind::WouldBlock.into())
+            }
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    impl AsyncRead for MockStream {}
+
+    impl AsyncWrite for MockStream {
+        fn shutdown(&mut self) -> Poll<(), io::Error> {
+            Ok(Async::Ready(()))
+        }
+    }
+
+    struct NoopService;
+
+    impl Service for NoopService {
+        type T = Value;
+        type E = Value;
+        type RequestFuture = Result<Value, Value>;
+        type NotificationFuture = Result<(), ()>;
+
+        fn handle_request(&mut self, _method: &str, _params: Value) -> Self::RequestFuture {
+            Err(Value::Null)
+        }
+
+        fn handle_notification(
+            &mut self,
+            _method: &str,
+            _params: Value,
+        ) -> Self::NotificationFuture {
+            Ok(())
+        }
+    }
+
+    struct NoopServiceBuilder;
+
+    impl ServiceBuilder for NoopServiceBuilder {
+        type Service = NoopService;
+
+        fn build(self, _client: Client) -> NoopService {
+            NoopService
+        }
+    }
+
+    #[test]
+    fn shutdown_waits_for_notifications_to_be_flushed() {
+        let writable = Arc::new(AtomicBool::new(false));
+        let stream = MockStream {
+            writable: writable.clone(),
+        };
+        let (mut endpoint, client) = Endpoint::new(stream, NoopServiceBuilder, Codec);
+        let mut ack = client.notify("update", json!({}));
+        client.shutdown();
+
+        future::lazy(move || {
+            // The notification can't be written yet, so the endpoint
+            // must keep running, without acknowledging it.
+            assert_eq!(endpoint.poll().unwrap(), Async::NotReady);
+            assert_eq!(endpoint.poll().unwrap(), Async::NotReady);
+            assert!(ack.poll().unwrap().is_not_ready());
+
+            writable.store(true, Ordering::SeqCst);
+            assert_eq!(endpoint.poll().unwrap(), Async::Ready(()));
+            drop(endpoint);
+            match ack.poll() {
+                Ok(Async::Ready(())) => {}
+                Ok(Async::NotReady) => panic!("notification not acknowledged"),
+                Err(RpcError::AckCanceled) => panic!("notification canceled"),
+                Err(e) => panic!("unexpected error: {:?}", e),
+            }
+            Ok::<(), ()>(())
+        })
+        .wait()
+        .unwrap();
+    }
+}
@@ -1,5 +1,7 @@
 use serde_json::error::Category;
 use serde_json::error::Error as SerdeError;
+use std::error;
+use std::fmt;
 use std::io;
 
 #[derive(Debug)]
@@ -19,8 +21,29 @@ impl From<SerdeError> for DecodeError {
     }
 }
 
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            DecodeError::Truncated => write!(f, "message is truncated"),
+            DecodeError::Io(ref e) => write!(f, "io error while decoding message: {}", e),
+            DecodeError::InvalidJson => write!(f, "message is not a valid Xi-RPC message"),
+        }
+    }
+}
+
+impl error::Error for DecodeError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match *self {
+            DecodeError::Io(ref e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
 #[derive(Debug)]
 pub enum RpcError {
     ResponseCanceled,
     AckCanceled,
+    /// The connection to the Xi-RPC endpoint is closed.
+    Disconnected,
 }
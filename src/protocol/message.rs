@@ -1,10 +1,11 @@
 use serde::{Deserialize, Deserializer, Serializer};
-use serde_json::{from_reader, to_vec, Value};
+use serde_json::{from_reader, from_slice, from_str, to_vec, Value};
+use std::convert::TryFrom;
 use std::io::Read;
 
 use super::errors::*;
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 #[serde(untagged)]
 pub enum Message {
     Request(Request),
@@ -12,7 +13,7 @@ pub enum Message {
     Notification(Notification),
 }
 
-#[derive(Serialize, Clone, Debug, Deserialize)]
+#[derive(Serialize, PartialEq, Clone, Debug, Deserialize)]
 pub struct Request {
     pub id: u64,
     pub method: String,
@@ -44,7 +45,7 @@ where
     }
 }
 
-#[derive(Debug, Deserialize, Serialize, Clone)]
+#[derive(Debug, PartialEq, Deserialize, Serialize, Clone)]
 pub struct Response {
     pub id: u64,
     #[serde(flatten)]
@@ -66,6 +67,15 @@ pub struct Notification {
     pub params: Value,
 }
 
+impl Notification {
+    pub fn new(method: &str, params: Value) -> Notification {
+        Notification {
+            method: method.to_owned(),
+            params,
+        }
+    }
+}
+
 impl Message {
     pub fn decode<R>(rd: &mut R) -> Result<Message, DecodeError>
     where
@@ -94,6 +104,22 @@ impl Message {
     }
 }
 
+impl<'a> TryFrom<&'a [u8]> for Message {
+    type Error = DecodeError;
+
+    fn try_from(buf: &'a [u8]) -> Result<Message, DecodeError> {
+        Ok(from_slice(buf)?)
+    }
+}
+
+impl<'a> TryFrom<&'a str> for Message {
+    type Error = DecodeError;
+
+    fn try_from(s: &'a str) -> Result<Message, DecodeError> {
+        Ok(from_str(s)?)
+    }
+}
+
 #[test]
 fn test_decode_message_ok() {
     let s = r#"{"id": 1, "result": "foo"}"#;
@@ -117,3 +143,27 @@ fn test_decode_message_err() {
     assert_eq!(actual.id, expected.id);
     assert_eq!(actual.result, expected.result);
 }
+
+#[test]
+fn test_new_notification() {
+    let notification = Notification::new("update", json!({"view_id": "view-id-1"}));
+    assert_eq!(notification.method, "update");
+    assert_eq!(notification.params, json!({"view_id": "view-id-1"}));
+}
+
+#[test]
+fn test_try_from() {
+    let s = r#"{"method": "update", "params": {}}"#;
+    let expected = Message::Notification(Notification::new("update", json!({})));
+    assert_eq!(Message::try_from(s).unwrap(), expected);
+    assert_eq!(Message::try_from(s.as_bytes()).unwrap(), expected);
+
+    match Message::try_from(r#"{"method": "update""#) {
+        Err(DecodeError::Truncated) => {}
+        other => panic!("expected a truncated message, got {:?}", other),
+    }
+    match Message::try_from(&b"[1, 2, 3]"[..]) {
+        Err(DecodeError::InvalidJson) => {}
+        other => panic!("expected invalid json, got {:?}", other),
+    }
+}
@@ -6,6 +6,8 @@ pub mod message;
 pub mod server;
 pub mod transport;
 
-pub use self::client::{Ack, Client, Response};
+pub use self::client::{Ack, Client, ConnectionState, Response};
+pub use self::codec::{Codec, MessageCodec, StrictCodec};
 pub use self::endpoint::Endpoint;
+pub use self::message::Notification;
 pub use self::server::{IntoStaticFuture, Service, ServiceBuilder};
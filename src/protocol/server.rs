@@ -6,6 +6,7 @@ use serde_json::Value;
 use tokio::io::{AsyncRead, AsyncWrite};
 
 use super::client::Client;
+use super::codec::MessageCodec;
 use super::message::Response as ResponseMessage;
 use super::message::{Message, Notification, Request};
 use super::transport::Transport;
@@ -42,9 +43,9 @@ impl<S: Service> Server<S> {
         }
     }
 
-    pub fn send_responses<T: AsyncRead + AsyncWrite>(
+    pub fn send_responses<T: AsyncRead + AsyncWrite, C: MessageCodec>(
         &mut self,
-        sink: &mut Transport<T>,
+        sink: &mut Transport<T, C>,
     ) -> Poll<(), io::Error> {
         trace!("Server: flushing responses");
         while let Ok(poll) = self.pending_responses.poll() {
@@ -2,19 +2,20 @@ use std::io;
 
 use futures::{AsyncSink, Poll, Sink, StartSend, Stream};
 use tokio::io::{AsyncRead, AsyncWrite};
-use tokio_codec::{Decoder, Framed};
+use tokio_codec::Framed;
 
-use super::codec::Codec;
+use super::codec::{Codec, MessageCodec};
 use super::message::Message;
 
-pub struct Transport<T: AsyncRead + AsyncWrite>(Framed<T, Codec>);
+pub struct Transport<T: AsyncRead + AsyncWrite, C: MessageCodec = Codec>(Framed<T, C>);
 
-impl<T> Transport<T>
+impl<T, C> Transport<T, C>
 where
     T: AsyncRead + AsyncWrite,
+    C: MessageCodec,
 {
-    pub fn new(stream: T) -> Self {
-        Transport(Codec.framed(stream))
+    pub fn new(stream: T, codec: C) -> Self {
+        Transport(Framed::new(stream, codec))
     }
 
     pub fn send(&mut self, message: Message) {
@@ -28,9 +29,10 @@ where
     }
 }
 
-impl<T> Stream for Transport<T>
+impl<T, C> Stream for Transport<T, C>
 where
     T: AsyncRead + AsyncWrite,
+    C: MessageCodec,
 {
     type Item = Message;
     type Error = io::Error;
@@ -40,9 +42,10 @@ where
     }
 }
 
-impl<T> Sink for Transport<T>
+impl<T, C> Sink for Transport<T, C>
 where
     T: AsyncRead + AsyncWrite,
+    C: MessageCodec,
 {
     type SinkItem = Message;
     type SinkError = io::Error;
@@ -1,4 +1,45 @@
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Default, PartialEq, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AlertSeverity {
+    #[default]
+    Info,
+    Warning,
+    Error,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct Alert {
     pub msg: String,
+    #[serde(default)]
+    pub severity: AlertSeverity,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+}
+
+#[test]
+fn deserialize_alert_without_severity() {
+    use serde_json;
+
+    let s = r#"{"msg": "file changed on disk"}"#;
+    let alert = Alert {
+        msg: "file changed on disk".to_string(),
+        severity: AlertSeverity::Info,
+        title: None,
+    };
+    let deserialized: Result<Alert, _> = serde_json::from_str(s);
+    assert_eq!(deserialized.unwrap(), alert);
+}
+
+#[test]
+fn deserialize_alert_with_severity() {
+    use serde_json;
+
+    let s = r#"{"msg": "plugin crashed", "severity": "error", "title": "syntect"}"#;
+    let alert = Alert {
+        msg: "plugin crashed".to_string(),
+        severity: AlertSeverity::Error,
+        title: Some("syntect".to_string()),
+    };
+    let deserialized: Result<Alert, _> = serde_json::from_str(s);
+    assert_eq!(deserialized.unwrap(), alert);
 }
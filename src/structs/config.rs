@@ -1,12 +1,12 @@
 use crate::ViewId;
 
-#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct ConfigChanged {
     pub view_id: ViewId,
     pub changes: ConfigChanges,
 }
 
-#[derive(Debug, PartialEq, Serialize, Deserialize, Default)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Default)]
 pub struct ConfigChanges {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub font_face: Option<String>,
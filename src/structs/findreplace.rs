@@ -1,6 +1,6 @@
 use super::view::ViewId;
 
-#[derive(Debug, Serialize, Deserialize, PartialEq)]
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
 pub struct Query {
     pub id: u64,
     pub chars: Option<String>,
@@ -11,19 +11,30 @@ pub struct Query {
     pub lines: Vec<u64>,
 }
 
-#[derive(Debug, Serialize, Deserialize, PartialEq)]
+/// A single query of a `"multi_find"` command.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct FindQuery {
+    pub chars: String,
+    pub case_sensitive: bool,
+    pub regex: bool,
+    pub whole_words: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<u64>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
 pub struct FindStatus {
     pub view_id: ViewId,
     pub queries: Vec<Query>,
 }
 
-#[derive(Debug, Serialize, Deserialize, PartialEq)]
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
 pub struct Status {
     pub chars: String,
     pub preserve_case: Option<bool>,
 }
 
-#[derive(Debug, Serialize, Deserialize, PartialEq)]
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
 pub struct ReplaceStatus {
     pub view_id: ViewId,
     pub status: Status,
@@ -73,4 +84,28 @@ mod test {
 
         assert_eq!(deserialized.unwrap(), replace_status);
     }
+
+    #[test]
+    fn test_findquery() {
+        use crate::structs::findreplace::FindQuery;
+        use serde_json;
+
+        let query = FindQuery {
+            chars: "foo".to_string(),
+            case_sensitive: true,
+            regex: false,
+            whole_words: false,
+            id: None,
+        };
+        assert_eq!(
+            serde_json::to_value(&query).unwrap(),
+            json!({"chars": "foo", "case_sensitive": true, "regex": false, "whole_words": false})
+        );
+
+        let query = FindQuery {
+            id: Some(2),
+            ..query
+        };
+        assert_eq!(serde_json::to_value(&query).unwrap()["id"], json!(2));
+    }
 }
@@ -1,12 +1,36 @@
 use super::view::ViewId;
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct AvailableLanguages {
     pub languages: Vec<String>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+impl AvailableLanguages {
+    /// Return the language names sorted alphabetically, ignoring case.
+    pub fn sorted_languages(&self) -> Vec<&str> {
+        let mut languages: Vec<&str> = self.languages.iter().map(String::as_str).collect();
+        languages.sort_by_cached_key(|name| name.to_lowercase());
+        languages
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct LanguageChanged {
     pub view_id: ViewId,
     pub language_id: String,
 }
+
+#[test]
+fn sorted_languages_ignores_case() {
+    let languages = AvailableLanguages {
+        languages: vec![
+            "rust".to_string(),
+            "Markdown".to_string(),
+            "JSON".to_string(),
+        ],
+    };
+    assert_eq!(
+        languages.sorted_languages(),
+        vec!["JSON", "Markdown", "rust"]
+    );
+}
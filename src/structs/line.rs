@@ -1,4 +1,6 @@
-use serde::{self, Deserialize, Deserializer};
+use serde::ser::SerializeSeq;
+use serde::{self, Deserialize, Deserializer, Serializer};
+use std::convert::TryFrom;
 
 #[derive(Default, Debug, PartialEq, Clone)]
 pub struct StyleDef {
@@ -7,15 +9,29 @@ pub struct StyleDef {
     pub style_id: u64,
 }
 
-#[derive(Default, Deserialize, Debug, PartialEq, Clone)]
+impl StyleDef {
+    pub fn new(offset: i64, length: u64, style_id: u64) -> StyleDef {
+        StyleDef {
+            offset,
+            length,
+            style_id,
+        }
+    }
+}
+
+#[derive(Default, Serialize, Deserialize, Debug, PartialEq, Clone)]
 pub struct Line {
     #[serde(default)]
     pub text: String,
     #[serde(default)]
     pub cursor: Vec<u64>,
-    #[serde(default, deserialize_with = "deserialize_styles")]
+    #[serde(
+        default,
+        deserialize_with = "deserialize_styles",
+        serialize_with = "serialize_styles"
+    )]
     pub styles: Vec<StyleDef>,
-    #[serde(rename = "ln")]
+    #[serde(rename = "ln", skip_serializing_if = "Option::is_none")]
     pub line_num: Option<u64>,
 }
 
@@ -39,13 +55,28 @@ where
     for i in 0..nb_styles {
         styles.push(StyleDef {
             offset: v[i * 3],
-            length: v[i * 3 + 1] as u64,   // FIXME: this can panic
-            style_id: v[i * 3 + 2] as u64, // FIXME: this can panic
+            length: u64::try_from(v[i * 3 + 1])
+                .map_err(|_| serde::de::Error::custom("style length is negative"))?,
+            style_id: u64::try_from(v[i * 3 + 2])
+                .map_err(|_| serde::de::Error::custom("style id is negative"))?,
         });
     }
     Ok(styles)
 }
 
+pub fn serialize_styles<S>(styles: &[StyleDef], serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    let mut seq = serializer.serialize_seq(Some(styles.len() * 3))?;
+    for style in styles {
+        seq.serialize_element(&style.offset)?;
+        seq.serialize_element(&style.length)?;
+        seq.serialize_element(&style.style_id)?;
+    }
+    seq.end()
+}
+
 #[test]
 fn deserialize_line_with_styles() {
     use super::Line;
@@ -93,3 +124,59 @@ fn deserialize_line_with_no_style() {
     let deserialized: Result<Line, _> = serde_json::from_str(s);
     assert_eq!(deserialized.unwrap(), line);
 }
+
+#[test]
+fn deserialize_line_with_negative_styles() {
+    use super::Line;
+    use serde_json;
+
+    let s = r#"{"text":"Bar","styles":[0,-1,2]}"#;
+    let err = serde_json::from_str::<Line>(s).unwrap_err();
+    assert!(err.to_string().contains("style length is negative"));
+
+    let s = r#"{"text":"Bar","styles":[0,1,-2]}"#;
+    let err = serde_json::from_str::<Line>(s).unwrap_err();
+    assert!(err.to_string().contains("style id is negative"));
+}
+
+#[test]
+fn serialize_line_with_styles() {
+    use serde_json;
+
+    let line = Line {
+        text: "Bar".to_string(),
+        cursor: vec![0],
+        styles: vec![
+            StyleDef {
+                offset: -1,
+                length: 1,
+                style_id: 2,
+            },
+            StyleDef {
+                offset: 3,
+                length: 4,
+                style_id: 5,
+            },
+        ],
+        line_num: Some(1),
+    };
+    let serialized = serde_json::to_value(&line).unwrap();
+    assert_eq!(
+        serialized,
+        json!({"text": "Bar", "cursor": [0], "styles": [-1, 1, 2, 3, 4, 5], "ln": 1})
+    );
+    assert_eq!(serde_json::from_value::<Line>(serialized).unwrap(), line);
+}
+
+#[test]
+fn new_style_def() {
+    assert_eq!(StyleDef::default(), StyleDef::new(0, 0, 0));
+    assert_eq!(
+        StyleDef::new(-1, 2, 3),
+        StyleDef {
+            offset: -1,
+            length: 2,
+            style_id: 3,
+        }
+    );
+}
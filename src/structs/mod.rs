@@ -13,10 +13,10 @@ mod theme;
 mod update;
 mod view;
 
-pub use self::alert::Alert;
+pub use self::alert::{Alert, AlertSeverity};
 pub use self::config::ConfigChanged;
 pub use self::config::ConfigChanges;
-pub use self::findreplace::{FindStatus, Query, ReplaceStatus, Status};
+pub use self::findreplace::{FindQuery, FindStatus, Query, ReplaceStatus, Status};
 pub use self::language::{AvailableLanguages, LanguageChanged};
 pub use self::line::{Line, StyleDef};
 pub use self::modifyselection::ModifySelection;
@@ -29,6 +29,6 @@ pub use self::plugins::UpdateCmds;
 pub use self::position::Position;
 pub use self::scroll_to::ScrollTo;
 pub use self::style::Style;
-pub use self::theme::{AvailableThemes, ThemeChanged, ThemeSettings};
+pub use self::theme::{theme_to_css, AvailableThemes, ThemeChanged, ThemeSettings};
 pub use self::update::Update;
 pub use self::view::{MeasureWidth, ViewId};
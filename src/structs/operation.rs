@@ -11,14 +11,16 @@ pub enum OperationType {
     Insert,
 }
 
-#[derive(Deserialize, Debug, PartialEq, Clone)]
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
 pub struct Operation {
     #[serde(rename = "op")]
     #[serde(deserialize_with = "deserialize_operation_type")]
+    #[serde(serialize_with = "serialize_operation_type")]
     pub operation_type: OperationType,
     #[serde(rename = "n")]
     pub nb_lines: u64,
     #[serde(rename = "ln")]
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub line_num: Option<u64>,
     #[serde(default)]
     pub lines: Vec<Line>,
@@ -41,6 +43,23 @@ where
     }
 }
 
+fn serialize_operation_type<S>(
+    operation_type: &OperationType,
+    se: S,
+) -> ::std::result::Result<S::Ok, S::Error>
+where
+    S: serde::Serializer,
+{
+    let s = match *operation_type {
+        OperationType::Copy => "copy",
+        OperationType::Skip => "skip",
+        OperationType::Invalidate => "invalidate",
+        OperationType::Update => "update",
+        OperationType::Insert => "ins",
+    };
+    se.serialize_str(s)
+}
+
 #[test]
 fn deserialize_operation_from_value() {
     use serde_json;
@@ -131,3 +150,29 @@ fn deserialize_copy() {
     let deserialized: Result<Operation, _> = serde_json::from_str(s);
     assert_eq!(deserialized.unwrap(), operation);
 }
+
+#[test]
+fn serialize_operation() {
+    use serde_json;
+
+    let operation = Operation {
+        operation_type: OperationType::Insert,
+        nb_lines: 1,
+        line_num: None,
+        lines: vec![Line {
+            cursor: vec![],
+            styles: vec![],
+            text: "foo".to_owned(),
+            line_num: Some(3),
+        }],
+    };
+    let serialized = serde_json::to_value(&operation).unwrap();
+    assert_eq!(
+        serialized,
+        json!({"op": "ins", "n": 1, "lines": [{"text": "foo", "cursor": [], "styles": [], "ln": 3}]})
+    );
+    assert_eq!(
+        serde_json::from_value::<Operation>(serialized).unwrap(),
+        operation
+    );
+}
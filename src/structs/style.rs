@@ -1,4 +1,4 @@
-#[derive(Default, Deserialize, Debug, PartialEq, Clone)]
+#[derive(Default, Serialize, Deserialize, Debug, PartialEq, Clone)]
 pub struct Style {
     pub id: u64,
     pub fg_color: Option<u32>,
@@ -1,12 +1,127 @@
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct AvailableThemes {
     pub themes: Vec<String>,
 }
 
+impl AvailableThemes {
+    /// Return the theme names sorted alphabetically, ignoring case.
+    pub fn sorted_themes(&self) -> Vec<&str> {
+        let mut themes: Vec<&str> = self.themes.iter().map(String::as_str).collect();
+        themes.sort_by_cached_key(|name| name.to_lowercase());
+        themes
+    }
+}
+
 pub type ThemeSettings = ::syntect::highlighting::ThemeSettings;
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct ThemeChanged {
     pub name: String,
     pub theme: ThemeSettings,
 }
+
+// syntect's `ThemeSettings` does not implement `PartialEq`, so the themes
+// are compared through their serialized form, which covers all the fields
+// whatever the version of syntect.
+impl PartialEq for ThemeChanged {
+    fn eq(&self, other: &ThemeChanged) -> bool {
+        self.name == other.name
+            && ::serde_json::to_value(&self.theme).expect("failed to serialize theme")
+                == ::serde_json::to_value(&other.theme).expect("failed to serialize theme")
+    }
+}
+
+/// Render the colors of a theme as a block of CSS custom properties
+/// (`--xi-background`, `--xi-foreground`, ...), declared on `:root`.
+/// Colors the theme doesn't define are left out.
+pub fn theme_to_css(theme: &ThemeSettings) -> String {
+    let colors = [
+        ("background", theme.background),
+        ("foreground", theme.foreground),
+        ("caret", theme.caret),
+        ("line-highlight", theme.line_highlight),
+        ("selection", theme.selection),
+        ("gutter", theme.gutter),
+        ("gutter-foreground", theme.gutter_foreground),
+        ("find-highlight", theme.find_highlight),
+        ("find-highlight-foreground", theme.find_highlight_foreground),
+        ("accent", theme.accent),
+    ];
+
+    let mut css = String::from(":root {\n");
+    for (name, color) in colors.iter() {
+        if let Some(c) = color {
+            css.push_str(&format!(
+                "    --xi-{}: #{:02x}{:02x}{:02x}{:02x};\n",
+                name, c.r, c.g, c.b, c.a
+            ));
+        }
+    }
+    css.push_str("}\n");
+    css
+}
+
+#[test]
+fn theme_to_css_skips_missing_colors() {
+    use syntect::highlighting::Color;
+
+    let theme = ThemeSettings {
+        background: Some(Color {
+            r: 0x00,
+            g: 0x2b,
+            b: 0x36,
+            a: 0xff,
+        }),
+        selection: Some(Color {
+            r: 0x07,
+            g: 0x36,
+            b: 0x42,
+            a: 0x80,
+        }),
+        ..Default::default()
+    };
+
+    assert_eq!(
+        theme_to_css(&theme),
+        ":root {\n    --xi-background: #002b36ff;\n    --xi-selection: #07364280;\n}\n"
+    );
+}
+
+#[test]
+fn sorted_themes_ignores_case() {
+    let themes = AvailableThemes {
+        themes: vec![
+            "Solarized (dark)".to_string(),
+            "Base16".to_string(),
+            "InspiredGitHub".to_string(),
+        ],
+    };
+    assert_eq!(
+        themes.sorted_themes(),
+        vec!["Base16", "InspiredGitHub", "Solarized (dark)"]
+    );
+}
+
+#[test]
+fn theme_changed_eq() {
+    use syntect::highlighting::Color;
+
+    let theme_changed = |name: &str, alpha| ThemeChanged {
+        name: name.to_string(),
+        theme: ThemeSettings {
+            background: Some(Color {
+                r: 0,
+                g: 0,
+                b: 0,
+                a: alpha,
+            }),
+            ..Default::default()
+        },
+    };
+    assert_eq!(theme_changed("Base16", 0xff), theme_changed("Base16", 0xff));
+    assert_ne!(theme_changed("Base16", 0xff), theme_changed("Base16", 0x80));
+    assert_ne!(
+        theme_changed("Base16", 0xff),
+        theme_changed("Solarized", 0xff)
+    );
+}
@@ -1,4 +1,4 @@
-use serde::{Deserialize, Deserializer};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
 use crate::Operation;
 use crate::ViewId;
@@ -39,6 +39,39 @@ impl<'de> Deserialize<'de> for Update {
     }
 }
 
+// Borrowed counterparts of `InnerUpdate` and `UpdateHelper`, used to
+// serialize an `Update` without cloning its operations.
+#[derive(Serialize)]
+struct InnerUpdateRef<'a> {
+    rev: Option<u64>,
+    #[serde(rename = "ops")]
+    operations: &'a [Operation],
+    pristine: bool,
+}
+
+#[derive(Serialize)]
+struct UpdateHelperRef<'a> {
+    update: InnerUpdateRef<'a>,
+    view_id: ViewId,
+}
+
+impl Serialize for Update {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        UpdateHelperRef {
+            update: InnerUpdateRef {
+                rev: self.rev,
+                operations: &self.operations,
+                pristine: self.pristine,
+            },
+            view_id: self.view_id,
+        }
+        .serialize(serializer)
+    }
+}
+
 #[test]
 fn deserialize_update() {
     use serde_json;
@@ -83,3 +116,39 @@ fn deserialize_update() {
     let deserialized: Result<Update, _> = serde_json::from_str(s);
     assert_eq!(deserialized.unwrap(), update);
 }
+
+#[test]
+fn serialize_update() {
+    use serde_json;
+    use std::str::FromStr;
+
+    use super::operation::{Operation, OperationType};
+
+    let update = Update {
+        operations: vec![Operation {
+            operation_type: OperationType::Copy,
+            nb_lines: 2,
+            line_num: Some(1),
+            lines: vec![],
+        }],
+        pristine: false,
+        rev: Some(3),
+        view_id: FromStr::from_str("view-id-1").unwrap(),
+    };
+    let serialized = serde_json::to_value(&update).unwrap();
+    assert_eq!(
+        serialized,
+        json!({
+            "update": {
+                "ops": [{"op": "copy", "n": 2, "ln": 1, "lines": []}],
+                "pristine": false,
+                "rev": 3
+            },
+            "view_id": "view-id-1"
+        })
+    );
+    assert_eq!(
+        serde_json::from_value::<Update>(serialized).unwrap(),
+        update
+    );
+}
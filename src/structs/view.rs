@@ -50,6 +50,26 @@ impl Error for IdParseError {
 #[derive(Debug, PartialEq, Eq, Hash, Copy, Clone, Ord, PartialOrd)]
 pub struct ViewId(pub usize);
 
+impl ViewId {
+    /// Sentinel id that can be used before a real view id is assigned.
+    pub const INVALID: ViewId = ViewId(usize::MAX);
+
+    pub fn new(n: usize) -> ViewId {
+        ViewId::from(n)
+    }
+
+    /// Return `false` if this id is [`ViewId::INVALID`].
+    pub fn is_valid(&self) -> bool {
+        *self != ViewId::INVALID
+    }
+}
+
+impl From<usize> for ViewId {
+    fn from(n: usize) -> ViewId {
+        ViewId(n)
+    }
+}
+
 impl FromStr for ViewId {
     type Err = IdParseError;
     fn from_str(s: &str) -> Result<ViewId, Self::Err> {
@@ -128,6 +148,13 @@ mod tests {
         assert_eq!("view-id-1234".to_string(), ViewId(1234).to_string());
     }
     #[test]
+    fn new() {
+        assert_eq!(ViewId(3), ViewId::new(3));
+        assert_eq!(ViewId(3), ViewId::from(3));
+        assert!(ViewId::new(3).is_valid());
+        assert!(!ViewId::INVALID.is_valid());
+    }
+    #[test]
     fn serialize() {
         assert_eq!(json!("view-id-1"), to_value(&ViewId(1)).unwrap());
     }